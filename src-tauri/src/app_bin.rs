@@ -11,6 +11,7 @@ use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command as TokioCommand};
 
 use crate::app_paths::{ensure_app_dirs, resolve_app_bin_tool};
+use crate::http_client::configured_proxy_url;
 
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -48,6 +49,46 @@ fn apply_env(cmd: &mut TokioCommand, env: &Option<HashMap<String, String>>) {
     }
 }
 
+/// Environment that hands the configured network proxy to tools opening their own
+/// connections. The URL carries the keyring password, so it is kept out of argv where any
+/// local user could read it from the process list. An explicit proxy flag or `env` entry
+/// from the caller still wins.
+fn proxy_env(
+    app: &AppHandle,
+    binary_name: &str,
+    args: &[String],
+) -> Result<Vec<(&'static str, String)>, String> {
+    let Some(proxy) = configured_proxy_url(app)? else {
+        return Ok(Vec::new());
+    };
+    let base = binary_name.trim().to_ascii_lowercase();
+    // aria2 only speaks HTTP(S) proxies and would otherwise connect directly. A version
+    // probe opens no connection, so it may still run.
+    if base.trim_end_matches(".exe") == "aria2c"
+        && !proxy.starts_with("http")
+        && !args.iter().any(|a| {
+            a == "--version" || a.starts_with("--all-proxy") || a.starts_with("--no-proxy")
+        })
+    {
+        let scheme = proxy.split("://").next().unwrap_or_default();
+        return Err(format!(
+            "aria2 cannot use a {}:// proxy; set an http:// proxy or turn off aria2 downloads",
+            scheme
+        ));
+    }
+    Ok([
+        "http_proxy",
+        "https_proxy",
+        "all_proxy",
+        "HTTP_PROXY",
+        "HTTPS_PROXY",
+        "ALL_PROXY",
+    ]
+    .into_iter()
+    .map(|key| (key, proxy.clone()))
+    .collect())
+}
+
 #[tauri::command]
 pub async fn run_app_bin_tool(
     app_handle: AppHandle,
@@ -58,10 +99,12 @@ pub async fn run_app_bin_tool(
 ) -> Result<AppBinRunResult, String> {
     let _ = ensure_app_dirs(&app_handle)?;
     let path = resolve_bin_path(&app_handle, &binary_name)?;
+    let proxy_env = proxy_env(&app_handle, &binary_name, &args)?;
 
     let run = async {
         let mut cmd = TokioCommand::new(&path);
         cmd.args(&args)
+            .envs(proxy_env)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .kill_on_drop(true);
@@ -105,6 +148,7 @@ pub async fn start_app_bin_tool(
 
     let mut cmd = TokioCommand::new(&path);
     cmd.args(&args)
+        .envs(proxy_env(&app_handle, &binary_name, &args)?)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .kill_on_drop(true);
@@ -3,6 +3,9 @@ use std::path::PathBuf;
 
 use tauri::Manager;
 
+use crate::tool_sources::load_tool_sources;
+use crate::tool_specs::{tool_spec, tool_specs};
+
 pub const PORTABLE_MARKER_FILE: &str = "HalalDL.portable.json";
 pub const PORTABLE_DATA_DIR: &str = "portable-data";
 pub const STATE_DIR: &str = "state";
@@ -12,17 +15,6 @@ pub const ARCHIVE_DIR: &str = "download-archive";
 pub const UPDATES_DIR: &str = "updates";
 pub const CACHE_DIR: &str = "cache";
 pub const YTDLP_CACHE_DIR: &str = "yt-dlp";
-pub const MANAGED_TOOL_IDS: &[&str] = &["yt-dlp", "ffmpeg", "aria2", "deno"];
-
-fn managed_tool_file_name(tool_id: &str) -> Option<&'static str> {
-    match tool_id {
-        "yt-dlp" => Some("yt-dlp.exe"),
-        "ffmpeg" => Some("ffmpeg.exe"),
-        "aria2" => Some("aria2c.exe"),
-        "deno" => Some("deno.exe"),
-        _ => None,
-    }
-}
 
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -160,20 +152,26 @@ pub fn get_missing_app_managed_tools(
     let paths = ensure_app_dirs(&app_handle)?;
     let bin_dir = PathBuf::from(&paths.bin_dir);
     let requested = tool_ids.unwrap_or_else(|| {
-        MANAGED_TOOL_IDS
+        tool_specs()
             .iter()
-            .map(|tool_id| (*tool_id).to_string())
+            .map(|spec| spec.id().to_string())
             .collect()
     });
 
+    // Tools with no build for this OS/CPU cannot be installed, so they never count as missing.
+    let registry = load_tool_sources(&app_handle).ok();
     let mut missing = Vec::new();
 
     for tool_id in requested {
-        let Some(file_name) = managed_tool_file_name(&tool_id) else {
+        let Ok(spec) = tool_spec(&tool_id) else {
             return Err(format!("Unsupported managed tool id: {}", tool_id));
         };
+        let available = registry
+            .as_ref()
+            .and_then(|registry| registry.tool(spec.source_id()).ok())
+            .is_none_or(|sources| sources.available_here());
 
-        if !bin_dir.join(file_name).exists() {
+        if available && !bin_dir.join(spec.primary_binary()).exists() {
             missing.push(tool_id);
         }
     }
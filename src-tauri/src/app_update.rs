@@ -1,7 +1,13 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::download::{download_to_temp, sha256_of_path};
+use crate::checksums::{fetch_text, find_checksum_for_names};
+use crate::download::{discard_partial, download_to_temp, sha256_of_path};
+use crate::transfers::{begin_transfer, CANCELLED_ERROR};
+use crate::update_signature::{update_signing_key_id, verify_update_signature};
+
+/// Transfer key for the app update download; pass it to `cancel_tool_download`.
+pub const APP_UPDATE_JOB_ID: &str = "app-update";
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -47,69 +53,6 @@ fn ps_quote(input: &str) -> String {
     input.replace('\'', "''")
 }
 
-async fn fetch_text(app_handle: &tauri::AppHandle, url: &str) -> Result<String, String> {
-    let client = reqwest::Client::builder()
-        .user_agent(format!("HalalDL/{}", app_handle.package_info().version))
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
-    if !response.status().is_success() {
-        return Err(format!("HTTP {}: {}", response.status(), url));
-    }
-
-    response.text().await.map_err(|e| e.to_string())
-}
-
-fn find_checksum_for_names(text: &str, filenames: &[&str]) -> Option<String> {
-    let targets: Vec<String> = filenames.iter().map(|f| f.to_lowercase()).collect();
-    let mut best: Option<(usize, String)> = None;
-
-    for raw in text.lines() {
-        let line = raw.trim().trim_end_matches('\r');
-        if line.is_empty() {
-            continue;
-        }
-
-        if let Some((name, hash)) = line.split_once(':') {
-            let name = name.trim().to_lowercase();
-            if let Some(idx) = targets.iter().position(|t| t == &name) {
-                let hash = hash.trim();
-                if !hash.is_empty() {
-                    let hash = hash.to_lowercase();
-                    if idx == 0 {
-                        return Some(hash);
-                    }
-                    if best.as_ref().map(|(b, _)| idx < *b).unwrap_or(true) {
-                        best = Some((idx, hash));
-                    }
-                }
-            }
-        }
-
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let hash = parts[0].trim();
-            let mut name = parts[1].trim();
-            name = name.trim_start_matches('*');
-            let name = name.to_lowercase();
-            if let Some(idx) = targets.iter().position(|t| t == &name) {
-                if !hash.is_empty() {
-                    let hash = hash.to_lowercase();
-                    if idx == 0 {
-                        return Some(hash);
-                    }
-                    if best.as_ref().map(|(b, _)| idx < *b).unwrap_or(true) {
-                        best = Some((idx, hash));
-                    }
-                }
-            }
-        }
-    }
-
-    best.map(|(_, hash)| hash)
-}
-
 fn move_verified_file(temp_path: &Path, dest_path: &Path) -> Result<(), String> {
     if let Some(parent) = dest_path.parent() {
         if !parent.exists() {
@@ -316,6 +259,18 @@ $best |
     }
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiedAppUpdate {
+    pub path: String,
+    pub sha256: String,
+    /// Minisign key id of the release key that signed the installer.
+    pub key_id: String,
+    pub trusted_comment: String,
+}
+
+/// Download the installer, then require both the published SHA-256 and a minisign
+/// signature (`signature_url`, default `<url>.sig`) from the built-in release key.
 #[tauri::command]
 pub async fn download_and_verify_app_update(
     app_handle: tauri::AppHandle,
@@ -323,13 +278,18 @@ pub async fn download_and_verify_app_update(
     dest: String,
     checksum_url: String,
     asset_name: String,
-) -> Result<String, String> {
+    signature_url: Option<String>,
+    background: Option<bool>,
+) -> Result<VerifiedAppUpdate, String> {
     let dest_path = PathBuf::from(&dest);
     if let Some(parent) = dest_path.parent() {
         if !parent.exists() {
             fs::create_dir_all(parent).map_err(|e| e.to_string())?;
         }
     }
+    // Fail before downloading anything if this build cannot verify signatures.
+    update_signing_key_id()?;
+    let transfer = begin_transfer(&app_handle, APP_UPDATE_JOB_ID, background.unwrap_or(false));
 
     let checksum_text = fetch_text(&app_handle, &checksum_url).await?;
     let expected = find_checksum_for_names(
@@ -340,17 +300,39 @@ pub async fn download_and_verify_app_update(
         ],
     )
     .ok_or_else(|| format!("Checksum not found for {}", asset_name))?;
-
-    let temp_path = download_to_temp(&app_handle, "app-update", &url, &dest_path).await?;
+    let signature_url = signature_url
+        .filter(|u| !u.trim().is_empty())
+        .unwrap_or_else(|| format!("{}.sig", url));
+    let signature = fetch_text(&app_handle, &signature_url)
+        .await
+        .map_err(|e| format!("Update signature unavailable: {}", e))?;
+
+    let temp_path = download_to_temp(&app_handle, APP_UPDATE_JOB_ID, &url, &dest_path).await?;
+    if transfer.is_cancelled() {
+        discard_partial(&temp_path);
+        return Err(CANCELLED_ERROR.to_string());
+    }
     let actual = sha256_of_path(&temp_path)?;
     if actual.to_lowercase() != expected.to_lowercase() {
-        let _ = fs::remove_file(&temp_path);
+        discard_partial(&temp_path);
         return Err(format!(
             "Checksum mismatch for {} (expected {}, got {})",
             asset_name, expected, actual
         ));
     }
+    let verified = match verify_update_signature(&temp_path, &signature) {
+        Ok(verified) => verified,
+        Err(e) => {
+            discard_partial(&temp_path);
+            return Err(e);
+        }
+    };
 
     move_verified_file(&temp_path, &dest_path)?;
-    Ok(dest)
+    Ok(VerifiedAppUpdate {
+        path: dest,
+        sha256: actual.to_lowercase(),
+        key_id: verified.key_id,
+        trusted_comment: verified.trusted_comment,
+    })
 }
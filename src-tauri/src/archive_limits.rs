@@ -0,0 +1,259 @@
+//! Limits on what tool archive extraction and manual staging may write, so a zip bomb or a
+//! wrong file is rejected before it fills the disk. Rejections render as
+//! `Rejected [<kind>]: ...` so the UI can tell them apart from other install errors.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::fs_utils::available_space;
+
+/// Entries (files and directories) one archive may list.
+pub const MAX_ENTRIES: usize = 10_000;
+/// Largest single file an archive may unpack to or a manual stage may copy.
+pub const MAX_ENTRY_SIZE: u64 = 1024 * 1024 * 1024;
+/// Sum of the unpacked sizes of every entry in one archive.
+pub const MAX_TOTAL_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+/// Unpacked bytes allowed per archive byte.
+pub const MAX_COMPRESSION_RATIO: u64 = 100;
+/// Highly compressible small files (licences, headers) are exempt from the ratio check.
+const RATIO_EXEMPT_SIZE: u64 = 16 * 1024 * 1024;
+/// Free space left over on the target volume after extracting.
+const DISK_SPACE_MARGIN: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum ArchiveLimitError {
+    #[serde(rename_all = "camelCase")]
+    TooManyEntries { count: usize, limit: usize },
+    #[serde(rename_all = "camelCase")]
+    EntryTooLarge { name: String, size: u64, limit: u64 },
+    #[serde(rename_all = "camelCase")]
+    TotalTooLarge { size: u64, limit: u64 },
+    #[serde(rename_all = "camelCase")]
+    CompressionRatio {
+        name: String,
+        ratio: u64,
+        limit: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    InsufficientDiskSpace {
+        path: String,
+        needed: u64,
+        available: u64,
+    },
+}
+
+impl ArchiveLimitError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TooManyEntries { .. } => "too-many-entries",
+            Self::EntryTooLarge { .. } => "entry-too-large",
+            Self::TotalTooLarge { .. } => "total-too-large",
+            Self::CompressionRatio { .. } => "compression-ratio",
+            Self::InsufficientDiskSpace { .. } => "insufficient-disk-space",
+        }
+    }
+}
+
+fn megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+}
+
+impl fmt::Display for ArchiveLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rejected [{}]: ", self.kind())?;
+        match self {
+            Self::TooManyEntries { count, limit } => {
+                write!(f, "archive lists {} entries (limit {})", count, limit)
+            }
+            Self::EntryTooLarge { name, size, limit } => write!(
+                f,
+                "{} is {} (limit {})",
+                name,
+                megabytes(*size),
+                megabytes(*limit)
+            ),
+            Self::TotalTooLarge { size, limit } => write!(
+                f,
+                "archive unpacks to {} (limit {})",
+                megabytes(*size),
+                megabytes(*limit)
+            ),
+            Self::CompressionRatio { name, ratio, limit } => write!(
+                f,
+                "{} is compressed {}:1 (limit {}:1), which looks like a zip bomb",
+                name, ratio, limit
+            ),
+            Self::InsufficientDiskSpace {
+                path,
+                needed,
+                available,
+            } => write!(
+                f,
+                "{} needed on the volume of {} but only {} is free",
+                megabytes(*needed),
+                path,
+                megabytes(*available)
+            ),
+        }
+    }
+}
+
+impl From<ArchiveLimitError> for String {
+    fn from(error: ArchiveLimitError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Kind of an `ArchiveLimitError` rendered into `message`, e.g. `entry-too-large`.
+pub fn rejection_kind(message: &str) -> Option<&str> {
+    let start = message.find("Rejected [")? + "Rejected [".len();
+    let end = start + message[start..].find(']')?;
+    Some(&message[start..end])
+}
+
+/// Fail with `InsufficientDiskSpace` unless `needed` bytes (plus a margin) fit in `dir`.
+/// Volumes whose free space cannot be read are let through.
+pub fn ensure_disk_space(dir: &Path, needed: u64) -> Result<(), ArchiveLimitError> {
+    match available_space(dir) {
+        Some(available) if available < needed.saturating_add(DISK_SPACE_MARGIN) => {
+            Err(ArchiveLimitError::InsufficientDiskSpace {
+                path: dir.display().to_string(),
+                needed: needed.saturating_add(DISK_SPACE_MARGIN),
+                available,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_ratio(name: &str, unpacked: u64, packed: u64) -> Result<(), ArchiveLimitError> {
+    if unpacked < RATIO_EXEMPT_SIZE {
+        return Ok(());
+    }
+    let ratio = unpacked / packed.max(1);
+    if ratio > MAX_COMPRESSION_RATIO {
+        return Err(ArchiveLimitError::CompressionRatio {
+            name: name.to_string(),
+            ratio,
+            limit: MAX_COMPRESSION_RATIO,
+        });
+    }
+    Ok(())
+}
+
+/// Running totals for one extraction, checked against the limits as entries are read.
+pub struct ExtractionBudget {
+    archive_name: String,
+    archive_size: u64,
+    /// Free space on the target volume when extraction started.
+    available: Option<u64>,
+    entries: usize,
+    declared: u64,
+    written: u64,
+}
+
+impl ExtractionBudget {
+    pub fn new(archive_path: &Path, dest_dir: &Path) -> Self {
+        Self {
+            archive_name: archive_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            archive_size: std::fs::metadata(archive_path)
+                .map(|metadata| metadata.len())
+                .unwrap_or(0),
+            available: available_space(dest_dir)
+                .map(|available| available.saturating_sub(DISK_SPACE_MARGIN)),
+            entries: 0,
+            declared: 0,
+            written: 0,
+        }
+    }
+
+    /// Count an entry listed by the archive. `packed` is its compressed size when the
+    /// format stores one per entry (zip); streamed formats are checked as a whole instead.
+    pub fn check_entry(
+        &mut self,
+        name: &str,
+        size: u64,
+        packed: Option<u64>,
+    ) -> Result<(), ArchiveLimitError> {
+        self.entries += 1;
+        if self.entries > MAX_ENTRIES {
+            return Err(ArchiveLimitError::TooManyEntries {
+                count: self.entries,
+                limit: MAX_ENTRIES,
+            });
+        }
+        if size > MAX_ENTRY_SIZE {
+            return Err(ArchiveLimitError::EntryTooLarge {
+                name: name.to_string(),
+                size,
+                limit: MAX_ENTRY_SIZE,
+            });
+        }
+        self.declared = self.declared.saturating_add(size);
+        if self.declared > MAX_TOTAL_SIZE {
+            return Err(ArchiveLimitError::TotalTooLarge {
+                size: self.declared,
+                limit: MAX_TOTAL_SIZE,
+            });
+        }
+        match packed {
+            Some(packed) => check_ratio(name, size, packed),
+            None => check_ratio(&self.archive_name, self.declared, self.archive_size),
+        }
+    }
+
+    /// Fail early when the files about to be extracted cannot fit on the target volume.
+    pub fn reserve(&self, dest_dir: &Path, needed: u64) -> Result<(), ArchiveLimitError> {
+        match self.available {
+            Some(available) if needed > available => {
+                Err(ArchiveLimitError::InsufficientDiskSpace {
+                    path: dest_dir.display().to_string(),
+                    needed: needed.saturating_add(DISK_SPACE_MARGIN),
+                    available: available.saturating_add(DISK_SPACE_MARGIN),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Most bytes the next file may be written with before a limit trips.
+    pub fn write_allowance(&self) -> u64 {
+        let mut allowance = MAX_ENTRY_SIZE.min(MAX_TOTAL_SIZE.saturating_sub(self.written));
+        if let Some(available) = self.available {
+            allowance = allowance.min(available.saturating_sub(self.written));
+        }
+        allowance
+    }
+
+    /// Account for `size` bytes actually written for `name`, which may differ from what the
+    /// archive declared.
+    pub fn record_written(
+        &mut self,
+        name: &str,
+        dest_dir: &Path,
+        size: u64,
+    ) -> Result<(), ArchiveLimitError> {
+        self.written = self.written.saturating_add(size);
+        if size > MAX_ENTRY_SIZE {
+            return Err(ArchiveLimitError::EntryTooLarge {
+                name: name.to_string(),
+                size,
+                limit: MAX_ENTRY_SIZE,
+            });
+        }
+        if self.written > MAX_TOTAL_SIZE {
+            return Err(ArchiveLimitError::TotalTooLarge {
+                size: self.written,
+                limit: MAX_TOTAL_SIZE,
+            });
+        }
+        self.reserve(dest_dir, self.written)?;
+        check_ratio(&self.archive_name, self.written, self.archive_size)
+    }
+}
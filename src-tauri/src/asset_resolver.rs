@@ -0,0 +1,184 @@
+//! Picks the release asset built for this machine. Candidates must match one of the registry's
+//! name patterns; assets naming another OS or CPU architecture are rejected outright, so arm64
+//! hosts never fall back to x64 builds without saying so.
+
+use crate::download::ReleaseAsset;
+
+/// Target the asset has to run on; `os` / `arch` use `std::env::consts` values.
+#[derive(Clone, Copy)]
+pub struct HostPlatform {
+    pub os: &'static str,
+    pub arch: &'static str,
+}
+
+impl HostPlatform {
+    pub fn current() -> Self {
+        Self {
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+        }
+    }
+}
+
+const OS_TOKENS: &[(&str, &[&str])] = &[
+    (
+        "windows",
+        &[
+            "windows", "win32", "win64", "winarm", "-win-", "msvc", "mingw", ".exe",
+        ],
+    ),
+    ("linux", &["linux", "musl"]),
+    ("macos", &["macos", "darwin", "apple", "osx"]),
+];
+
+const ARCH_TOKENS: &[(&str, &[&str])] = &[
+    (
+        "x86_64",
+        &[
+            "x86_64", "x86-64", "amd64", "x64", "64bit", "win64", "linux64",
+        ],
+    ),
+    ("aarch64", &["aarch64", "arm64", "armv8"]),
+    ("x86", &["i386", "i686", "32bit", "win32", "_x86.", "-x86."]),
+    ("arm", &["armv7", "armhf", "armel"]),
+];
+
+/// Checksums, signatures and the like are never installable.
+const SIDECAR_SUFFIXES: &[&str] = &[
+    ".asc",
+    ".sig",
+    ".minisig",
+    ".sha256",
+    ".sha256sum",
+    ".sha512",
+    ".md5",
+    ".txt",
+    ".json",
+];
+
+/// Case-insensitive match of `name` against a pattern with `*` and `?` wildcards.
+pub fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` swallow one more character and retry.
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Which entries of `table` the (lower-case) asset name mentions.
+fn mentioned<'a>(table: &[(&'a str, &[&str])], name: &str) -> Vec<&'a str> {
+    // `x86_64` contains `x86`; strip it so 64-bit names do not read as 32-bit.
+    let without_x64 = name.replace("x86_64", "").replace("x86-64", "");
+    table
+        .iter()
+        .filter(|(id, tokens)| {
+            let haystack = if *id == "x86" { &without_x64 } else { name };
+            tokens.iter().any(|token| haystack.contains(token))
+        })
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+/// Score `name` for `host`; `None` when it must not be installed there.
+/// Earlier patterns win, then names that spell out the host architecture and OS.
+pub fn score_asset(name: &str, host: HostPlatform, patterns: &[&str]) -> Option<u32> {
+    let lower = name.to_lowercase();
+    if SIDECAR_SUFFIXES
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+    {
+        return None;
+    }
+    let pattern_index = patterns
+        .iter()
+        .position(|pattern| glob_matches(pattern, &lower))?;
+
+    let oses = mentioned(OS_TOKENS, &lower);
+    if !oses.is_empty() && !oses.contains(&host.os) {
+        return None;
+    }
+    let arches = mentioned(ARCH_TOKENS, &lower);
+    if !arches.is_empty() && !arches.contains(&host.arch) {
+        return None;
+    }
+    // Unlabelled builds are x64, except on macOS where they are universal binaries.
+    if arches.is_empty() && host.arch != "x86_64" && host.os != "macos" {
+        return None;
+    }
+
+    let mut score = 1000u32.saturating_sub(100 * pattern_index as u32);
+    if arches.contains(&host.arch) {
+        score += 20;
+    }
+    if oses.contains(&host.os) {
+        score += 10;
+    }
+    Some(score)
+}
+
+/// Release line a name carries, e.g. `[7, 1]` for `ffmpeg-n7.1-latest-win64-gpl-7.1.zip`:
+/// the first `-`/`_`-separated token that is a dotted number, with an optional `n`/`v`.
+/// Empty when there is none (`ffmpeg-master-latest-...`).
+fn release_line(name: &str) -> Vec<u32> {
+    name.split(['-', '_'])
+        .map(|token| token.trim_start_matches(['n', 'v']))
+        .filter(|token| token.contains('.'))
+        .find_map(|token| {
+            token
+                .split('.')
+                .map(|part| part.parse().ok())
+                .collect::<Option<Vec<u32>>>()
+        })
+        .unwrap_or_default()
+}
+
+/// Best asset for `host`, or an error naming what was available. Among equally good
+/// matches the highest release line wins, since one release can carry several (BtbN
+/// publishes `n6.1`, `n7.1`, ... builds side by side).
+pub fn select_release_asset(
+    assets: Vec<ReleaseAsset>,
+    host: HostPlatform,
+    patterns: &[&str],
+    release: &str,
+) -> Result<ReleaseAsset, String> {
+    let available: Vec<String> = assets.iter().map(|asset| asset.name.clone()).collect();
+    let best = assets
+        .into_iter()
+        .filter_map(|asset| {
+            let score = score_asset(&asset.name, host, patterns)?;
+            Some(((score, release_line(&asset.name)), asset))
+        })
+        // `max_by` keeps the last maximum; reverse so ties go to the first listed asset.
+        .rev()
+        .max_by(|(a, _), (b, _)| a.cmp(b));
+
+    match best {
+        Some((_, asset)) => Ok(asset),
+        None if available.is_empty() => Err(format!("{} has no release assets", release)),
+        None => Err(format!(
+            "{} has no {}/{} build matching {} (assets: {})",
+            release,
+            host.os,
+            host.arch,
+            patterns.join(" or "),
+            available.join(", ")
+        )),
+    }
+}
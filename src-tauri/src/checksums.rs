@@ -0,0 +1,149 @@
+//! SHA-256 verification for downloaded payloads: the app installer and every managed tool.
+//! Expected digests come from upstream checksum files or GitHub release asset digests.
+
+use std::path::Path;
+
+use crate::download::sha256_of_path;
+use crate::http_client::{shared_client, API_REQUEST_TIMEOUT};
+
+/// Where the expected SHA-256 of a download comes from.
+#[derive(Clone)]
+pub enum ExpectedChecksum {
+    /// Checksum file to fetch; `file_names` selects the line when it lists several files.
+    Remote {
+        url: String,
+        file_names: Vec<String>,
+    },
+    /// Digest already known, e.g. the `digest` field of a GitHub release asset.
+    Known(String),
+}
+
+impl ExpectedChecksum {
+    pub fn remote(url: impl Into<String>, file_name: &str) -> Self {
+        Self::Remote {
+            url: url.into(),
+            file_names: vec![file_name.to_string()],
+        }
+    }
+}
+
+pub async fn fetch_text(app_handle: &tauri::AppHandle, url: &str) -> Result<String, String> {
+    let client = shared_client(app_handle)?;
+
+    let response = client
+        .get(url)
+        .timeout(API_REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}: {}", response.status(), url));
+    }
+
+    response.text().await.map_err(|e| e.to_string())
+}
+
+/// Hash listed for the first matching name in a `sha256sum`-style or `name: hash` list.
+pub fn find_checksum_for_names(text: &str, filenames: &[&str]) -> Option<String> {
+    let targets: Vec<String> = filenames.iter().map(|f| f.to_lowercase()).collect();
+    let mut best: Option<(usize, String)> = None;
+
+    for raw in text.lines() {
+        let line = raw.trim().trim_end_matches('\r');
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some((name, hash)) = line.split_once(':') {
+            let name = name.trim().to_lowercase();
+            if let Some(idx) = targets.iter().position(|t| t == &name) {
+                let hash = hash.trim();
+                if !hash.is_empty() {
+                    let hash = hash.to_lowercase();
+                    if idx == 0 {
+                        return Some(hash);
+                    }
+                    if best.as_ref().map(|(b, _)| idx < *b).unwrap_or(true) {
+                        best = Some((idx, hash));
+                    }
+                }
+            }
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 2 {
+            let hash = parts[0].trim();
+            let mut name = parts[1].trim();
+            name = name.trim_start_matches('*');
+            let name = name.to_lowercase();
+            if let Some(idx) = targets.iter().position(|t| t == &name) {
+                if !hash.is_empty() {
+                    let hash = hash.to_lowercase();
+                    if idx == 0 {
+                        return Some(hash);
+                    }
+                    if best.as_ref().map(|(b, _)| idx < *b).unwrap_or(true) {
+                        best = Some((idx, hash));
+                    }
+                }
+            }
+        }
+    }
+
+    best.map(|(_, hash)| hash)
+}
+
+pub fn is_sha256_hex(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The digest of a single-file checksum (`<hash>`, `<hash>  name` or PowerShell
+/// `Get-FileHash` output). Ambiguous files listing several digests yield `None`.
+fn single_checksum(text: &str) -> Option<String> {
+    let mut hashes = text
+        .split(|c: char| c.is_whitespace() || c == ':' || c == '*')
+        .filter(|token| is_sha256_hex(token));
+    let hash = hashes.next()?;
+    if hashes.any(|other| !other.eq_ignore_ascii_case(hash)) {
+        return None;
+    }
+    Some(hash.to_lowercase())
+}
+
+/// Resolve the expected lowercase hex SHA-256 for a download.
+pub async fn resolve_expected_sha256(
+    app_handle: &tauri::AppHandle,
+    source: &ExpectedChecksum,
+) -> Result<String, String> {
+    match source {
+        ExpectedChecksum::Known(hash) => {
+            let hash = hash.trim().to_lowercase();
+            if !is_sha256_hex(&hash) {
+                return Err(format!("Invalid SHA-256 digest: {}", hash));
+            }
+            Ok(hash)
+        }
+        ExpectedChecksum::Remote { url, file_names } => {
+            let text = fetch_text(app_handle, url)
+                .await
+                .map_err(|e| format!("Failed to fetch checksum {}: {}", url, e))?;
+            let names: Vec<&str> = file_names.iter().map(String::as_str).collect();
+            find_checksum_for_names(&text, &names)
+                .filter(|hash| is_sha256_hex(hash))
+                .or_else(|| single_checksum(&text))
+                .ok_or_else(|| format!("Checksum for {} not found in {}", names.join(", "), url))
+        }
+    }
+}
+
+/// Hash `path` and compare it with `expected`; returns the verified digest.
+pub fn verify_sha256(path: &Path, expected: &str, label: &str) -> Result<String, String> {
+    let actual = sha256_of_path(path)?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(format!(
+            "Checksum mismatch for {} (expected {}, got {})",
+            label, expected, actual
+        ));
+    }
+    Ok(actual)
+}
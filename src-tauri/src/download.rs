@@ -4,24 +4,38 @@ use std::fs;
 use std::io::{Read, Write};
 use std::path::Path;
 use std::path::PathBuf;
-use std::time::Duration;
 use tauri::Emitter;
+use tokio_util::sync::CancellationToken;
 
-use crate::fs_utils::temp_path_for;
+use crate::asset_resolver::{glob_matches, select_release_asset, HostPlatform};
+use crate::checksums::{fetch_text, find_checksum_for_names, is_sha256_hex};
+use crate::fs_utils::{resume_meta_path_for, temp_path_for};
+use crate::http_client::{shared_client, API_REQUEST_TIMEOUT};
+use crate::rate_limit::throttle;
+use crate::transfers::{is_background_transfer, is_cancelled_error, or_cancelled, transfer_token};
 
 #[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DownloadProgress {
     pub tool: String,
     pub percentage: f64,
     pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloaded_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_per_second: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<u64>,
 }
 
-const MAX_DOWNLOAD_RETRIES: u8 = 3;
-const BROWSER_USER_AGENT: &str =
+pub const MAX_DOWNLOAD_RETRIES: u8 = 3;
+pub const BROWSER_USER_AGENT: &str =
     "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/136.0.0.0 Safari/537.36 HalalDL/1.0";
-const DEFAULT_ACCEPT_LANGUAGE: &str = "en-US,en;q=0.9";
+pub const DEFAULT_ACCEPT_LANGUAGE: &str = "en-US,en;q=0.9";
 
-fn sanitize_header_value(value: Option<String>, fallback: &'static str) -> String {
+pub fn sanitize_header_value(value: Option<String>, fallback: &'static str) -> String {
     value
         .map(|raw| {
             raw.chars()
@@ -40,46 +54,75 @@ pub fn emit_progress(app_handle: &tauri::AppHandle, tool: &str, percentage: f64,
             tool: tool.to_string(),
             percentage,
             status: status.to_string(),
+            downloaded_bytes: None,
+            total_bytes: None,
+            bytes_per_second: None,
+            eta_seconds: None,
         },
     );
 }
 
-/// Download any URL directly to a local file (used for thumbnails).
-#[tauri::command]
-pub async fn download_url_to_file(
-    url: String,
-    dest: String,
-    referer: Option<String>,
-    user_agent: Option<String>,
-    accept_language: Option<String>,
-) -> Result<String, String> {
-    use tokio::io::AsyncWriteExt;
-
-    let user_agent = sanitize_header_value(user_agent, BROWSER_USER_AGENT);
-    let accept_language = sanitize_header_value(accept_language, DEFAULT_ACCEPT_LANGUAGE);
-    let client = reqwest::Client::builder()
-        .build()
-        .map_err(|e| format!("HTTP client error: {}", e))?;
+/// Like `emit_progress`, with byte counters, throughput and ETA attached.
+pub fn emit_transfer_progress(
+    app_handle: &tauri::AppHandle,
+    tool: &str,
+    downloaded: u64,
+    total: u64,
+    bytes_per_second: u64,
+) {
+    let percentage = if total > 0 {
+        (downloaded as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    };
+    let eta_seconds = if bytes_per_second > 0 && total > downloaded {
+        Some((total - downloaded) / bytes_per_second)
+    } else {
+        None
+    };
+    let _ = app_handle.emit(
+        "download-progress",
+        DownloadProgress {
+            tool: tool.to_string(),
+            percentage,
+            status: format!(
+                "Downloading {:.1} / {:.1} MB ({:.1} MB/s)...",
+                downloaded as f64 / 1_048_576.0,
+                total as f64 / 1_048_576.0,
+                bytes_per_second as f64 / 1_048_576.0
+            ),
+            downloaded_bytes: Some(downloaded),
+            total_bytes: Some(total),
+            bytes_per_second: Some(bytes_per_second),
+            eta_seconds,
+        },
+    );
+}
 
+/// GET with the browser-like headers media CDNs expect.
+pub fn media_request(
+    client: &reqwest::Client,
+    url: &str,
+    referer: Option<&str>,
+    user_agent: &str,
+    accept_language: &str,
+) -> reqwest::RequestBuilder {
     let mut req = client
-        .get(&url)
+        .get(url)
         .header(
             reqwest::header::ACCEPT,
             "image/avif,image/webp,image/apng,image/svg+xml,image/*,video/*,audio/*,*/*;q=0.8",
         )
         .header(reqwest::header::ACCEPT_LANGUAGE, accept_language)
         .header(reqwest::header::USER_AGENT, user_agent);
-    if let Some(ref r) = referer {
+    if let Some(r) = referer {
         req = req.header(reqwest::header::REFERER, r);
     }
+    req
+}
 
-    let resp = req
-        .send()
-        .await
-        .map_err(|e| format!("Download failed: {}", e))?;
-    if !resp.status().is_success() {
-        return Err(format!("HTTP {}: {}", resp.status(), url));
-    }
+/// Refuse HTML/JSON bodies (login walls, API errors) posing as media.
+pub fn ensure_media_content_type(resp: &reqwest::Response) -> Result<(), String> {
     if let Some(content_type) = resp.headers().get(reqwest::header::CONTENT_TYPE) {
         let content_type = content_type.to_str().unwrap_or("").to_ascii_lowercase();
         if content_type.starts_with("text/html") || content_type.starts_with("application/json") {
@@ -89,6 +132,39 @@ pub async fn download_url_to_file(
             ));
         }
     }
+    Ok(())
+}
+
+/// Download any URL directly to a local file (used for thumbnails).
+#[tauri::command]
+pub async fn download_url_to_file(
+    app_handle: tauri::AppHandle,
+    url: String,
+    dest: String,
+    referer: Option<String>,
+    user_agent: Option<String>,
+    accept_language: Option<String>,
+) -> Result<String, String> {
+    use tokio::io::AsyncWriteExt;
+
+    let user_agent = sanitize_header_value(user_agent, BROWSER_USER_AGENT);
+    let accept_language = sanitize_header_value(accept_language, DEFAULT_ACCEPT_LANGUAGE);
+    let client = shared_client(&app_handle)?;
+
+    let resp = media_request(
+        &client,
+        &url,
+        referer.as_deref(),
+        &user_agent,
+        &accept_language,
+    )
+    .send()
+    .await
+    .map_err(|e| format!("Download failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {}: {}", resp.status(), url));
+    }
+    ensure_media_content_type(&resp)?;
 
     let dest_path = Path::new(&dest);
     if let Some(parent) = dest_path.parent() {
@@ -109,6 +185,7 @@ pub async fn download_url_to_file(
             continue;
         }
         wrote_any = true;
+        throttle(&app_handle, chunk.len(), false).await;
         file.write_all(&chunk)
             .await
             .map_err(|e| format!("File write failed: {}", e))?;
@@ -128,6 +205,7 @@ pub async fn download_url_to_file(
 /// Post an x-www-form-urlencoded body and return the response text.
 #[tauri::command]
 pub async fn post_form_for_text(
+    app_handle: tauri::AppHandle,
     url: String,
     body: String,
     referer: Option<String>,
@@ -137,9 +215,7 @@ pub async fn post_form_for_text(
 ) -> Result<String, String> {
     let user_agent = sanitize_header_value(user_agent, BROWSER_USER_AGENT);
     let accept_language = sanitize_header_value(accept_language, DEFAULT_ACCEPT_LANGUAGE);
-    let client = reqwest::Client::builder()
-        .build()
-        .map_err(|e| format!("HTTP client error: {}", e))?;
+    let client = shared_client(&app_handle)?;
 
     let mut req = client
         .post(&url)
@@ -191,165 +267,241 @@ pub fn sha256_of_path(path: &Path) -> Result<String, String> {
         .collect::<String>())
 }
 
-pub async fn resolve_latest_aria2_zip_url(app_handle: &tauri::AppHandle) -> Result<String, String> {
-    let client = reqwest::Client::builder()
-        .user_agent(format!("HalalDL/{}", app_handle.package_info().version))
-        .build()
-        .map_err(|e| e.to_string())?;
+/// A GitHub release asset plus the SHA-256 GitHub publishes for it, when present.
+#[derive(Clone)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub url: String,
+    pub sha256: Option<String>,
+}
+
+fn release_asset(asset: &serde_json::Value) -> Option<ReleaseAsset> {
+    let name = asset.get("name").and_then(|v| v.as_str())?;
+    let url = asset.get("browser_download_url").and_then(|v| v.as_str())?;
+    let sha256 = asset
+        .get("digest")
+        .and_then(|v| v.as_str())
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+        .filter(|hash| is_sha256_hex(hash))
+        .map(str::to_lowercase);
+    Some(ReleaseAsset {
+        name: name.to_string(),
+        url: url.to_string(),
+        sha256,
+    })
+}
+
+async fn fetch_github_release(
+    app_handle: &tauri::AppHandle,
+    repo: &str,
+    tag: Option<&str>,
+) -> Result<serde_json::Value, String> {
+    let client = shared_client(app_handle)?;
+    let url = match tag {
+        Some(tag) => format!(
+            "https://api.github.com/repos/{}/releases/tags/{}",
+            repo, tag
+        ),
+        None => format!("https://api.github.com/repos/{}/releases/latest", repo),
+    };
 
     let res = client
-        .get("https://api.github.com/repos/aria2/aria2/releases/latest")
+        .get(&url)
         .header("Accept", "application/vnd.github+json")
+        .timeout(API_REQUEST_TIMEOUT)
         .send()
         .await
         .map_err(|e| e.to_string())?;
 
     if !res.status().is_success() {
-        return Err(format!("GitHub API failed with status: {}", res.status()));
+        return Err(format!(
+            "GitHub API failed with status: {} ({})",
+            res.status(),
+            repo
+        ));
     }
 
-    let json: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
+    res.json().await.map_err(|e| e.to_string())
+}
+
+/// Best asset of a release for this machine. Older releases list their checksums in the
+/// release notes instead of the asset digest, so those are used as a fallback.
+fn pick_release_asset(
+    json: &serde_json::Value,
+    patterns: &[&str],
+    excluded: &[&str],
+    release: &str,
+) -> Result<ReleaseAsset, String> {
     let assets = json
         .get("assets")
         .and_then(|v| v.as_array())
-        .ok_or_else(|| "Missing assets in GitHub release response".to_string())?;
-
-    for asset in assets {
-        let name = asset.get("name").and_then(|v| v.as_str()).unwrap_or("");
-        if name.ends_with(".zip")
-            && !name.ends_with(".zip.asc")
-            && name.to_lowercase().contains("win-64bit")
-        {
-            if let Some(url) = asset.get("browser_download_url").and_then(|v| v.as_str()) {
-                return Ok(url.to_string());
-            }
-        }
-    }
-
-    Err("No matching aria2 Windows zip asset found".to_string())
-}
-
-fn find_ffmpeg_essentials_zip_url(json: &serde_json::Value) -> Option<String> {
-    let assets = json.get("assets").and_then(|v| v.as_array())?;
-    for asset in assets {
-        let name = asset.get("name").and_then(|v| v.as_str()).unwrap_or("");
-        let lower = name.to_lowercase();
-        if lower.ends_with(".zip") && lower.contains("essentials_build") {
-            if let Some(url) = asset.get("browser_download_url").and_then(|v| v.as_str()) {
-                return Some(url.to_string());
-            }
-        }
+        .map(|assets| {
+            assets
+                .iter()
+                .filter_map(release_asset)
+                .filter(|asset| !excluded.iter().any(|p| glob_matches(p, &asset.name)))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut found = select_release_asset(assets, HostPlatform::current(), patterns, release)?;
+    if found.sha256.is_none() {
+        let body = json.get("body").and_then(|v| v.as_str()).unwrap_or("");
+        found.sha256 = find_checksum_for_names(body, &[found.name.as_str()])
+            .filter(|hash| is_sha256_hex(hash));
     }
-    None
+    Ok(found)
 }
 
-pub async fn resolve_latest_ffmpeg_essentials_zip_url(
+/// Asset for this OS and architecture in release `tag` of `repo` (the latest when `None`),
+/// chosen from those matching `patterns` but not `excluded`. When that release has no
+/// suitable build and `tag_url` is set, the version it returns is tried.
+pub async fn resolve_github_release_asset(
     app_handle: &tauri::AppHandle,
-) -> Result<String, String> {
-    let client = reqwest::Client::builder()
-        .user_agent(format!("HalalDL/{}", app_handle.package_info().version))
-        .connect_timeout(Duration::from_secs(20))
-        .read_timeout(Duration::from_secs(45))
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    // Prefer GitHub latest so installs work when gyan.dev is down.
-    let latest_res = client
-        .get("https://api.github.com/repos/GyanD/codexffmpeg/releases/latest")
-        .header("Accept", "application/vnd.github+json")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if latest_res.status().is_success() {
-        let json: serde_json::Value = latest_res.json().await.map_err(|e| e.to_string())?;
-        if let Some(url) = find_ffmpeg_essentials_zip_url(&json) {
-            return Ok(url);
-        }
-    }
-
-    let version_res = client
-        .get("https://www.gyan.dev/ffmpeg/builds/release-version")
-        .send()
+    repo: &str,
+    tag: Option<&str>,
+    tag_url: Option<&str>,
+    patterns: &[&str],
+    excluded: &[&str],
+) -> Result<ReleaseAsset, String> {
+    let label = format!("{} {}", repo, tag.unwrap_or("latest release"));
+    let picked = fetch_github_release(app_handle, repo, tag)
         .await
-        .map_err(|e| e.to_string())?;
+        .and_then(|json| pick_release_asset(&json, patterns, excluded, &label));
+    let Some(tag_url) = tag_url.filter(|_| picked.is_err()) else {
+        return picked;
+    };
 
-    if !version_res.status().is_success() {
-        return Err(format!(
-            "Gyan FFmpeg version check failed with status: {}",
-            version_res.status()
-        ));
-    }
-
-    let version = version_res
-        .text()
-        .await
-        .map_err(|e| e.to_string())?
-        .trim()
+    let version = fetch_text(app_handle, tag_url)
+        .await?
         .split_whitespace()
         .next()
         .unwrap_or("")
         .trim_start_matches('v')
         .to_string();
-
     if version.is_empty() {
-        return Err("Gyan FFmpeg latest release version was empty".to_string());
+        return Err(format!("{} returned an empty version", tag_url));
     }
 
-    let release_res = client
-        .get(format!(
-            "https://api.github.com/repos/GyanD/codexffmpeg/releases/tags/{}",
-            version
-        ))
-        .header("Accept", "application/vnd.github+json")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    let json = fetch_github_release(app_handle, repo, Some(&version)).await?;
+    pick_release_asset(&json, patterns, excluded, &format!("{} {}", repo, version))
+}
 
-    if !release_res.status().is_success() {
-        return Err(format!(
-            "Gyan FFmpeg GitHub mirror lookup failed with status: {}",
-            release_res.status()
-        ));
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ResumeMeta {
+    url: String,
+    validator: String,
+}
+
+/// Strong ETag if the server sent one, otherwise Last-Modified. Weak ETags are
+/// not allowed in `If-Range`, so they never qualify.
+pub fn resume_validator(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let etag = headers
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && !v.starts_with("W/"));
+    if let Some(etag) = etag {
+        return Some(etag.to_string());
     }
+    headers
+        .get(reqwest::header::LAST_MODIFIED)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
 
-    let json: serde_json::Value = release_res.json().await.map_err(|e| e.to_string())?;
-    find_ffmpeg_essentials_zip_url(&json).ok_or_else(|| {
-        format!(
-            "No matching FFmpeg Essentials ZIP asset found for {}",
-            version
-        )
-    })
+/// Start offset of a `Content-Range: bytes <start>-<end>/<total>` header.
+pub fn content_range_start(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let value = headers.get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    let range = value.trim().strip_prefix("bytes")?.trim();
+    let (start, _) = range.split_once('-')?;
+    start.trim().parse().ok()
 }
 
+/// Remove a partial `.new` download together with its resume sidecar.
+pub fn discard_partial(temp_dest: &Path) {
+    let _ = fs::remove_file(temp_dest);
+    if let Ok(meta_path) = resume_meta_path_for(temp_dest) {
+        let _ = fs::remove_file(meta_path);
+    }
+}
+
+/// Bytes already on disk for `url` plus the validator they were fetched under.
+fn resumable_partial(temp_dest: &Path, url: &str) -> Option<(u64, String)> {
+    let meta_path = resume_meta_path_for(temp_dest).ok()?;
+    let raw = fs::read_to_string(meta_path).ok()?;
+    let meta: ResumeMeta = serde_json::from_str(&raw).ok()?;
+    if meta.url != url || meta.validator.is_empty() {
+        return None;
+    }
+    let len = fs::metadata(temp_dest).ok()?.len();
+    if len == 0 {
+        return None;
+    }
+    Some((len, meta.validator))
+}
+
+fn write_resume_meta(temp_dest: &Path, url: &str, validator: &str) -> Result<(), String> {
+    let meta_path = resume_meta_path_for(temp_dest)?;
+    let meta = ResumeMeta {
+        url: url.to_string(),
+        validator: validator.to_string(),
+    };
+    let raw = serde_json::to_string(&meta).map_err(|e| e.to_string())?;
+    fs::write(meta_path, raw).map_err(|e| e.to_string())
+}
+
+/// Download `url` to the `.new` temp file next to `dest`. Partial temp files left
+/// by an earlier attempt are continued with `Range`/`If-Range` when the server
+/// still serves the same representation. A transfer registered under `tool_name`
+/// can cancel it; the partial file is then removed instead of kept for resuming.
+/// Background transfers are throttled by the background bandwidth cap too.
 pub async fn download_to_temp(
     app_handle: &tauri::AppHandle,
     tool_name: &str,
     url: &str,
     dest: &PathBuf,
 ) -> Result<PathBuf, String> {
-    let client = reqwest::Client::builder()
-        .user_agent(format!("HalalDL/{}", app_handle.package_info().version))
-        .connect_timeout(Duration::from_secs(20))
-        .read_timeout(Duration::from_secs(45))
-        .build()
-        .map_err(|e| e.to_string())?;
+    let client = shared_client(app_handle)?;
+    let cancel = transfer_token(app_handle, tool_name);
+    let background = is_background_transfer(app_handle, tool_name);
 
+    let temp_dest = temp_path_for(dest)?;
     let mut last_error: Option<String> = None;
 
     for attempt in 1..=MAX_DOWNLOAD_RETRIES {
-        let result = download_file_once(&client, app_handle, tool_name, url, dest).await;
+        let result = download_file_once(
+            &client,
+            app_handle,
+            tool_name,
+            url,
+            dest,
+            cancel.as_ref(),
+            background,
+        )
+        .await;
         match result {
             Ok(temp_dest) => return Ok(temp_dest),
+            Err(e) if is_cancelled_error(&e) => {
+                discard_partial(&temp_dest);
+                return Err(e);
+            }
             Err(e) => {
                 last_error = Some(e);
                 if attempt < MAX_DOWNLOAD_RETRIES {
+                    let verb = if resumable_partial(&temp_dest, url).is_some() {
+                        "Resuming"
+                    } else {
+                        "Retrying"
+                    };
                     emit_progress(
                         app_handle,
                         tool_name,
                         0.0,
                         &format!(
-                            "Retrying download (attempt {}/{})...",
+                            "{} download (attempt {}/{})...",
+                            verb,
                             attempt + 1,
                             MAX_DOWNLOAD_RETRIES
                         ),
@@ -368,44 +520,91 @@ async fn download_file_once(
     tool_name: &str,
     url: &str,
     dest: &PathBuf,
+    cancel: Option<&CancellationToken>,
+    background: bool,
 ) -> Result<PathBuf, String> {
-    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    let temp_dest = temp_path_for(dest)?;
+    let partial = resumable_partial(&temp_dest, url);
 
-    if !response.status().is_success() {
-        return Err(format!(
-            "Download failed with status: {}",
-            response.status()
-        ));
+    let mut request = client.get(url);
+    if let Some((offset, validator)) = &partial {
+        request = request
+            .header(reqwest::header::RANGE, format!("bytes={}-", offset))
+            .header(reqwest::header::IF_RANGE, validator.as_str());
     }
+    let response = or_cancelled(cancel, request.send())
+        .await?
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
 
-    let total_size = response.content_length().unwrap_or(0);
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        discard_partial(&temp_dest);
+        return Err("Server rejected the resume range; restarting download".to_string());
+    }
+    if !status.is_success() {
+        return Err(format!("Download failed with status: {}", status));
+    }
+
+    let resume_offset = match &partial {
+        Some((offset, _)) if status == reqwest::StatusCode::PARTIAL_CONTENT => {
+            if content_range_start(response.headers()) != Some(*offset) {
+                discard_partial(&temp_dest);
+                return Err(
+                    "Server returned an unexpected byte range; restarting download".to_string(),
+                );
+            }
+            Some(*offset)
+        }
+        _ => None,
+    };
+
+    let mut file = match resume_offset {
+        Some(_) => fs::OpenOptions::new()
+            .append(true)
+            .open(&temp_dest)
+            .map_err(|e| e.to_string())?,
+        None => {
+            // Full response: either nothing to resume or the upstream file changed.
+            discard_partial(&temp_dest);
+            let file = fs::File::create(&temp_dest).map_err(|e| e.to_string())?;
+            if let Some(validator) = resume_validator(response.headers()) {
+                write_resume_meta(&temp_dest, url, &validator)?;
+            }
+            file
+        }
+    };
+
+    let mut downloaded: u64 = resume_offset.unwrap_or(0);
+    let total_size = response
+        .content_length()
+        .map(|len| len + downloaded)
+        .unwrap_or(0);
     if total_size > 0 {
         emit_progress(
             app_handle,
             tool_name,
-            0.0,
+            (downloaded as f64 / total_size as f64) * 100.0,
             &format!(
-                "Downloading 0.0 / {:.1} MB...",
+                "{} {:.1} / {:.1} MB...",
+                if downloaded > 0 {
+                    "Resuming at"
+                } else {
+                    "Downloading"
+                },
+                downloaded as f64 / 1_048_576.0,
                 total_size as f64 / 1_048_576.0
             ),
         );
     }
 
-    let mut downloaded: u64 = 0;
     let mut stream = response.bytes_stream();
 
-    let temp_dest = temp_path_for(dest)?;
-    if temp_dest.exists() {
-        let _ = fs::remove_file(&temp_dest);
-    }
-
-    let mut file = fs::File::create(&temp_dest).map_err(|e| e.to_string())?;
-
     let mut last_percentage = 0.0;
-    let mut last_unsized_emit = 0u64;
+    let mut last_unsized_emit = downloaded;
 
-    while let Some(item) = stream.next().await {
+    while let Some(item) = or_cancelled(cancel, stream.next()).await? {
         let chunk = item.map_err(|e| e.to_string())?;
+        or_cancelled(cancel, throttle(app_handle, chunk.len(), background)).await?;
         file.write_all(&chunk).map_err(|e| e.to_string())?;
         downloaded += chunk.len() as u64;
 
@@ -439,9 +638,17 @@ async fn download_file_once(
 
     let metadata = fs::metadata(&temp_dest).map_err(|e| e.to_string())?;
     if metadata.len() == 0 {
-        let _ = fs::remove_file(&temp_dest);
+        discard_partial(&temp_dest);
         return Err("Downloaded file is empty".to_string());
     }
+    if total_size > 0 && metadata.len() != total_size {
+        return Err(format!(
+            "Download incomplete ({} of {} bytes)",
+            metadata.len(),
+            total_size
+        ));
+    }
 
+    let _ = fs::remove_file(resume_meta_path_for(&temp_dest)?);
     Ok(temp_dest)
 }
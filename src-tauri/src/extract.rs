@@ -1,92 +1,505 @@
+use std::cell::Cell;
 use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
 
+use crate::archive_limits::{ArchiveLimitError, ExtractionBudget, MAX_ENTRIES};
+use crate::asset_resolver::glob_matches;
 use crate::download::emit_progress;
-use crate::fs_utils::{safe_replace_with_backup, temp_path_for};
+use crate::fs_utils::{make_executable, temp_path_for};
+use crate::tool_transaction::{replace_all, Replacement};
+
+/// File name of `entry_path` when it matches one of `targets` (case-insensitive; library
+/// targets such as `*.dll` use wildcards).
+fn matching_target(entry_path: &Path, targets: &[&str]) -> Option<String> {
+    let filename = entry_path.file_name()?.to_string_lossy().to_string();
+    targets
+        .iter()
+        .any(|t| glob_matches(t, &filename))
+        .then_some(filename)
+}
+
+/// `path` when it stays inside the extraction directory: relative, without `..` or a drive
+/// prefix. Mirrors `zip::read::ZipFile::enclosed_name` for the tar and 7z readers.
+fn enclosed_path(path: &Path) -> Option<PathBuf> {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        .then(|| path.to_path_buf())
+}
+
+/// Reports how far into the archive file extraction has read, one event per percent.
+struct ExtractProgress<'a> {
+    app_handle: &'a tauri::AppHandle,
+    tool_name: &'a str,
+    total: u64,
+    /// Off while a reader jumps around parsing headers at the end of the file.
+    enabled: Cell<bool>,
+    last_percent: Cell<u64>,
+}
+
+impl<'a> ExtractProgress<'a> {
+    fn new(app_handle: &'a tauri::AppHandle, tool_name: &'a str, archive: &fs::File) -> Self {
+        Self {
+            app_handle,
+            tool_name,
+            total: archive.metadata().map(|m| m.len()).unwrap_or(0),
+            enabled: Cell::new(true),
+            last_percent: Cell::new(0),
+        }
+    }
+
+    fn update(&self, position: u64) {
+        if !self.enabled.get() || self.total == 0 {
+            return;
+        }
+        let percent = (position.min(self.total) * 100) / self.total;
+        if percent <= self.last_percent.get() {
+            return;
+        }
+        self.last_percent.set(percent);
+        emit_progress(
+            self.app_handle,
+            self.tool_name,
+            percent as f64,
+            &format!(
+                "Extracting {:.1} / {:.1} MB...",
+                position.min(self.total) as f64 / 1_048_576.0,
+                self.total as f64 / 1_048_576.0
+            ),
+        );
+    }
+}
+
+/// The archive file, feeding its read position into `ExtractProgress`.
+struct ProgressReader<'a, R> {
+    inner: R,
+    position: u64,
+    progress: &'a ExtractProgress<'a>,
+}
+
+impl<'a, R> ProgressReader<'a, R> {
+    fn new(inner: R, progress: &'a ExtractProgress<'a>) -> Self {
+        Self {
+            inner,
+            position: 0,
+            progress,
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.position += read as u64;
+        self.progress.update(self.position);
+        Ok(read)
+    }
+}
+
+impl<R: Seek> Seek for ProgressReader<'_, R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.position = self.inner.seek(pos)?;
+        self.progress.update(self.position);
+        Ok(self.position)
+    }
+}
+
+/// Write one archive member to a `.new` file next to its destination, stopping as soon as it
+/// outgrows what `budget` still allows.
+fn stage_extracted(
+    reader: &mut (impl Read + ?Sized),
+    dest_dir: &Path,
+    target_name: &str,
+    budget: &mut ExtractionBudget,
+) -> Result<Replacement, String> {
+    let dest_file = dest_dir.join(target_name);
+    let temp_dest = temp_path_for(&dest_file)?;
+
+    if temp_dest.exists() {
+        let _ = fs::remove_file(&temp_dest);
+    }
+    let mut outfile = fs::File::create(&temp_dest)
+        .map_err(|e| format!("Failed to create output file {}: {}", target_name, e))?;
+    let allowance = budget.write_allowance();
+    let written = std::io::copy(&mut reader.take(allowance.saturating_add(1)), &mut outfile)
+        .map_err(|e| format!("Failed to extract file {}: {}", target_name, e))?;
+    if let Err(e) = budget.record_written(target_name, dest_dir, written) {
+        drop(outfile);
+        let _ = fs::remove_file(&temp_dest);
+        return Err(e.into());
+    }
+    outfile
+        .flush()
+        .map_err(|e| format!("Failed to flush file {}: {}", target_name, e))?;
+
+    let metadata = fs::metadata(&temp_dest)
+        .map_err(|e| format!("Failed to read metadata for {}: {}", target_name, e))?;
+    if metadata.len() == 0 {
+        let _ = fs::remove_file(&temp_dest);
+        return Err(format!("Extracted file {} is empty", target_name));
+    }
+
+    make_executable(&temp_dest)?;
+    Ok(Replacement {
+        dest: dest_file,
+        incoming: temp_dest,
+    })
+}
+
+fn missing_targets_error(missing: &[&str]) -> String {
+    format!(
+        "Expected files missing from the archive: {}",
+        missing.join(", ")
+    )
+}
+
+/// Targets that must be in the archive: every exact member name. Wildcard library patterns
+/// such as `*.dll` are optional, but at least one target has to turn up.
+fn missing_targets<'a>(targets: &[&'a str], found: &[String]) -> Vec<&'a str> {
+    if found.is_empty() {
+        return targets.to_vec();
+    }
+    targets
+        .iter()
+        .filter(|target| !target.contains(['*', '?']))
+        .filter(|target| !found.iter().any(|name| name.eq_ignore_ascii_case(target)))
+        .copied()
+        .collect()
+}
+
+/// Swap every staged member in together, so a pair like ffmpeg/ffprobe never ends up mixed.
+/// Nothing is swapped unless every required member was found; staged files are removed
+/// again if anything failed.
+fn activate_staged(
+    app_handle: &tauri::AppHandle,
+    tool_name: &str,
+    staged: Vec<Replacement>,
+    scanned: Result<Vec<String>, String>,
+    targets: &[&str],
+) -> Result<Vec<String>, String> {
+    emit_progress(
+        app_handle,
+        tool_name,
+        100.0,
+        "Activating extracted files...",
+    );
+    let temps: Vec<PathBuf> = staged.iter().map(|r| r.incoming.clone()).collect();
+    let result = scanned
+        .and_then(|found| {
+            let missing = missing_targets(targets, &found);
+            if missing.is_empty() {
+                Ok(found)
+            } else {
+                Err(missing_targets_error(&missing))
+            }
+        })
+        .and_then(|found| replace_all(app_handle, tool_name, staged, true).map(|()| found));
+    if result.is_err() {
+        for temp in &temps {
+            let _ = fs::remove_file(temp);
+        }
+    }
+    result
+}
+
+/// Archive formats, told apart by their leading bytes rather than the file name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    TarGz,
+    TarXz,
+    SevenZip,
+}
+
+impl ArchiveFormat {
+    fn detect(archive_path: &Path) -> Result<Self, String> {
+        let mut file =
+            fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+        let mut magic = [0u8; 6];
+        let read = file
+            .read(&mut magic)
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+        let magic = &magic[..read];
+        if magic.starts_with(b"PK\x03\x04") || magic.starts_with(b"PK\x05\x06") {
+            Ok(Self::Zip)
+        } else if magic.starts_with(&[0x1f, 0x8b]) {
+            Ok(Self::TarGz)
+        } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Ok(Self::TarXz)
+        } else if magic.starts_with(&[b'7', b'z', 0xbc, 0xaf, 0x27, 0x1c]) {
+            Ok(Self::SevenZip)
+        } else {
+            Err(format!(
+                "Unsupported archive format: {}",
+                archive_path.display()
+            ))
+        }
+    }
+}
 
 pub fn extract_from_zip(
     app_handle: &tauri::AppHandle,
     tool_name: &str,
-    zip_path: &PathBuf,
-    dest_dir: &PathBuf,
+    zip_path: &Path,
+    dest_dir: &Path,
     targets: Vec<&str>,
 ) -> Result<Vec<String>, String> {
-    emit_progress(app_handle, tool_name, 99.0, "Opening archive...");
+    emit_progress(app_handle, tool_name, 0.0, "Opening archive...");
     let file = fs::File::open(zip_path).map_err(|e| format!("Failed to open zip: {}", e))?;
-    let mut archive =
-        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read zip archive: {}", e))?;
-
-    let mut found_targets = Vec::new();
+    let progress = ExtractProgress::new(app_handle, tool_name, &file);
+    progress.enabled.set(false);
+    let mut archive = zip::ZipArchive::new(ProgressReader::new(file, &progress))
+        .map_err(|e| format!("Failed to read zip archive: {}", e))?;
+    progress.enabled.set(true);
 
     emit_progress(
         app_handle,
         tool_name,
-        99.0,
+        0.0,
         &format!("Scanning {} files...", archive.len()),
     );
+    if archive.len() > MAX_ENTRIES {
+        return Err(ArchiveLimitError::TooManyEntries {
+            count: archive.len(),
+            limit: MAX_ENTRIES,
+        }
+        .into());
+    }
 
+    // The central directory lists every size up front, so limits are checked before
+    // anything is written.
+    let mut budget = ExtractionBudget::new(zip_path, dest_dir);
+    let mut needed = 0u64;
     for i in 0..archive.len() {
-        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
-        let outpath = match file.enclosed_name() {
-            Some(path) => path.to_owned(),
-            None => continue,
-        };
+        let file = archive.by_index_raw(i).map_err(|e| e.to_string())?;
+        budget.check_entry(file.name(), file.size(), Some(file.compressed_size()))?;
+        let is_target = file
+            .enclosed_name()
+            .is_some_and(|path| matching_target(&path, &targets).is_some());
+        if is_target {
+            needed = needed.saturating_add(file.size());
+        }
+    }
+    budget.reserve(dest_dir, needed)?;
 
-        let filename = outpath.file_name().unwrap_or_default().to_string_lossy();
-
-        if targets
-            .iter()
-            .any(|&t| filename.to_lowercase() == t.to_lowercase())
-        {
-            let target_name = filename.to_string();
-            let dest_file = dest_dir.join(&target_name);
-            let temp_dest = temp_path_for(&dest_file)?;
-
-            emit_progress(
-                app_handle,
-                tool_name,
-                99.0,
-                &format!("Extracting {}...", target_name),
-            );
-
-            if temp_dest.exists() {
-                let _ = fs::remove_file(&temp_dest);
+    let mut staged = Vec::new();
+    let scanned = (|| {
+        let mut found_targets = Vec::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+            let outpath = match file.enclosed_name() {
+                Some(path) => path.to_owned(),
+                None => continue,
+            };
+
+            if let Some(target_name) = matching_target(&outpath, &targets) {
+                staged.push(stage_extracted(
+                    &mut file,
+                    dest_dir,
+                    &target_name,
+                    &mut budget,
+                )?);
+                found_targets.push(target_name);
             }
-            let mut outfile = fs::File::create(&temp_dest).map_err(|e| {
-                format!("Failed to create output file {}: {}", target_name, e)
-            })?;
-            std::io::copy(&mut file, &mut outfile)
-                .map_err(|e| format!("Failed to extract file {}: {}", target_name, e))?;
-            outfile
-                .flush()
-                .map_err(|e| format!("Failed to flush file {}: {}", target_name, e))?;
-
-            let metadata = fs::metadata(&temp_dest)
-                .map_err(|e| format!("Failed to read metadata for {}: {}", target_name, e))?;
-            if metadata.len() == 0 {
-                return Err(format!("Extracted file {} is empty", target_name));
+        }
+        Ok(found_targets)
+    })();
+
+    activate_staged(app_handle, tool_name, staged, scanned, &targets)
+}
+
+/// Extract `targets` from a tar stream, already decompressed by `reader`. Tar has no index,
+/// so limits are checked entry by entry as the stream is read.
+fn extract_from_tar(
+    app_handle: &tauri::AppHandle,
+    tool_name: &str,
+    reader: impl Read,
+    dest_dir: &Path,
+    targets: Vec<&str>,
+    mut budget: ExtractionBudget,
+) -> Result<Vec<String>, String> {
+    let mut archive = tar::Archive::new(reader);
+
+    let mut staged = Vec::new();
+    let scanned = (|| {
+        let mut found_targets = Vec::new();
+        let entries = archive
+            .entries()
+            .map_err(|e| format!("Failed to read tar archive: {}", e))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| format!("Failed to read tar archive: {}", e))?;
+            let outpath = entry.path().map_err(|e| e.to_string())?.into_owned();
+            budget.check_entry(&outpath.to_string_lossy(), entry.size(), None)?;
+            if !entry.header().entry_type().is_file() {
+                continue;
             }
+            let Some(outpath) = enclosed_path(&outpath) else {
+                continue;
+            };
 
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                let mut perms = metadata.permissions();
-                perms.set_mode(0o755);
-                fs::set_permissions(&temp_dest, perms).map_err(|e| e.to_string())?;
+            if let Some(target_name) = matching_target(&outpath, &targets) {
+                staged.push(stage_extracted(
+                    &mut entry,
+                    dest_dir,
+                    &target_name,
+                    &mut budget,
+                )?);
+                found_targets.push(target_name);
             }
+        }
+        Ok(found_targets)
+    })();
+
+    activate_staged(app_handle, tool_name, staged, scanned, &targets)
+}
+
+/// Same as `extract_from_zip` for `.tar.xz` archives (static Linux FFmpeg builds).
+pub fn extract_from_tar_xz(
+    app_handle: &tauri::AppHandle,
+    tool_name: &str,
+    archive_path: &Path,
+    dest_dir: &Path,
+    targets: Vec<&str>,
+) -> Result<Vec<String>, String> {
+    emit_progress(app_handle, tool_name, 0.0, "Opening archive...");
+    let file =
+        fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let progress = ExtractProgress::new(app_handle, tool_name, &file);
+    let reader = xz2::read::XzDecoder::new(ProgressReader::new(file, &progress));
+    let budget = ExtractionBudget::new(archive_path, dest_dir);
+    extract_from_tar(app_handle, tool_name, reader, dest_dir, targets, budget)
+}
 
-            safe_replace_with_backup(&dest_file, &temp_dest)?;
-            found_targets.push(target_name);
+/// Same as `extract_from_zip` for `.tar.gz` archives.
+pub fn extract_from_tar_gz(
+    app_handle: &tauri::AppHandle,
+    tool_name: &str,
+    archive_path: &Path,
+    dest_dir: &Path,
+    targets: Vec<&str>,
+) -> Result<Vec<String>, String> {
+    emit_progress(app_handle, tool_name, 0.0, "Opening archive...");
+    let file =
+        fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let progress = ExtractProgress::new(app_handle, tool_name, &file);
+    let reader = flate2::read::GzDecoder::new(ProgressReader::new(file, &progress));
+    let budget = ExtractionBudget::new(archive_path, dest_dir);
+    extract_from_tar(app_handle, tool_name, reader, dest_dir, targets, budget)
+}
+
+/// Same as `extract_from_zip` for `.7z` archives. Solid blocks are decoded front to back, so
+/// members that are not targets are read through and discarded.
+pub fn extract_from_7z(
+    app_handle: &tauri::AppHandle,
+    tool_name: &str,
+    archive_path: &Path,
+    dest_dir: &Path,
+    targets: Vec<&str>,
+) -> Result<Vec<String>, String> {
+    emit_progress(app_handle, tool_name, 0.0, "Opening archive...");
+    let file =
+        fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let progress = ExtractProgress::new(app_handle, tool_name, &file);
+    progress.enabled.set(false);
+    let mut archive = sevenz_rust::SevenZReader::new(
+        ProgressReader::new(file, &progress),
+        progress.total,
+        sevenz_rust::Password::empty(),
+    )
+    .map_err(|e| format!("Failed to read 7z archive: {}", e))?;
+    progress.enabled.set(true);
+
+    let files = &archive.archive().files;
+    if files.len() > MAX_ENTRIES {
+        return Err(ArchiveLimitError::TooManyEntries {
+            count: files.len(),
+            limit: MAX_ENTRIES,
+        }
+        .into());
+    }
+    let mut budget = ExtractionBudget::new(archive_path, dest_dir);
+    let mut needed = 0u64;
+    for entry in files {
+        budget.check_entry(entry.name(), entry.size(), None)?;
+        let path = PathBuf::from(entry.name().replace('\\', "/"));
+        if !entry.is_directory() && matching_target(&path, &targets).is_some() {
+            needed = needed.saturating_add(entry.size());
         }
     }
+    budget.reserve(dest_dir, needed)?;
+
+    let mut staged = Vec::new();
+    let mut found_targets = Vec::new();
+    let mut failure: Option<String> = None;
+    let walked = archive.for_each_entries(|entry, reader| {
+        let outpath = PathBuf::from(entry.name().replace('\\', "/"));
+        let target = if entry.is_directory() {
+            None
+        } else {
+            enclosed_path(&outpath).and_then(|path| matching_target(&path, &targets))
+        };
+        match target {
+            Some(target_name) => match stage_extracted(reader, dest_dir, &target_name, &mut budget)
+            {
+                Ok(replacement) => {
+                    staged.push(replacement);
+                    found_targets.push(target_name);
+                }
+                Err(e) => {
+                    failure = Some(e);
+                    return Ok(false);
+                }
+            },
+            None => {
+                std::io::copy(reader, &mut std::io::sink())?;
+            }
+        }
+        Ok(true)
+    });
+    let scanned = match (failure, walked) {
+        (Some(e), _) => Err(e),
+        (None, Err(e)) => Err(format!("Failed to read 7z archive: {}", e)),
+        (None, Ok(())) => Ok(found_targets),
+    };
+
+    activate_staged(app_handle, tool_name, staged, scanned, &targets)
+}
 
-    if found_targets.is_empty() {
-        return Err(format!(
-            "None of the expected files ({}) were found in the archive",
-            targets.join(", ")
-        ));
+/// Archive suffix of a release asset name, used to name the downloaded file.
+pub fn archive_extension(asset_name: &str) -> &'static str {
+    let lower = asset_name.to_lowercase();
+    if lower.ends_with(".tar.xz") {
+        "tar.xz"
+    } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+        "tar.gz"
+    } else if lower.ends_with(".7z") {
+        "7z"
+    } else {
+        "zip"
     }
+}
 
-    Ok(found_targets)
+/// Extract `targets` from a zip, tar.gz, tar.xz or 7z archive into `dest_dir`. The format
+/// is taken from the file signature, so a misnamed download still extracts.
+pub fn extract_archive(
+    app_handle: &tauri::AppHandle,
+    tool_name: &str,
+    archive_path: &Path,
+    dest_dir: &Path,
+    targets: Vec<&str>,
+) -> Result<Vec<String>, String> {
+    match ArchiveFormat::detect(archive_path)? {
+        ArchiveFormat::Zip => {
+            extract_from_zip(app_handle, tool_name, archive_path, dest_dir, targets)
+        }
+        ArchiveFormat::TarGz => {
+            extract_from_tar_gz(app_handle, tool_name, archive_path, dest_dir, targets)
+        }
+        ArchiveFormat::TarXz => {
+            extract_from_tar_xz(app_handle, tool_name, archive_path, dest_dir, targets)
+        }
+        ArchiveFormat::SevenZip => {
+            extract_from_7z(app_handle, tool_name, archive_path, dest_dir, targets)
+        }
+    }
 }
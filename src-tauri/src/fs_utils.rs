@@ -10,6 +10,15 @@ pub fn temp_path_for(path: &Path) -> Result<PathBuf, String> {
     Ok(path.with_file_name(format!("{}.new", file_name)))
 }
 
+/// Sidecar recording the URL and validator a partial `.new` download was started from.
+pub fn resume_meta_path_for(temp_path: &Path) -> Result<PathBuf, String> {
+    let file_name = temp_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Invalid target path: {}", temp_path.display()))?;
+    Ok(temp_path.with_file_name(format!("{}.resume", file_name)))
+}
+
 pub fn backup_path_for(path: &Path) -> Result<PathBuf, String> {
     let file_name = path
         .file_name()
@@ -18,12 +27,30 @@ pub fn backup_path_for(path: &Path) -> Result<PathBuf, String> {
     Ok(path.with_file_name(format!("{}.old", file_name)))
 }
 
+/// Mark a tool binary executable (`0o755`); a no-op on Windows.
+pub fn make_executable(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
 fn copy_replace(from: &Path, to: &Path) -> io::Result<()> {
     fs::copy(from, to)?;
     fs::remove_file(from)?;
     Ok(())
 }
 
+/// Rename `from` to `to`, falling back to copy+remove across volumes.
+pub fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    fs::rename(from, to).or_else(|_| copy_replace(from, to))
+}
+
 pub fn safe_replace_with_backup(dest: &Path, incoming: &Path) -> Result<(), String> {
     let backup = backup_path_for(dest)?;
 
@@ -61,3 +88,47 @@ pub fn safe_replace_with_backup(dest: &Path, incoming: &Path) -> Result<(), Stri
         }
     }
 }
+
+/// Bytes free for this user on the volume holding `dir`; `None` when it cannot be read.
+pub fn available_space(dir: &Path) -> Option<u64> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+        let wide: Vec<u16> = dir
+            .as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+        let mut available = 0u64;
+        let ok = unsafe {
+            GetDiskFreeSpaceExW(
+                wide.as_ptr(),
+                &mut available,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        (ok != 0).then_some(available)
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = std::ffi::CString::new(dir.as_os_str().as_bytes()).ok()?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+        // Field widths differ between platforms.
+        #[allow(clippy::unnecessary_cast)]
+        let available = (stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64);
+        Some(available)
+    }
+    #[cfg(not(any(windows, unix)))]
+    {
+        let _ = dir;
+        None
+    }
+}
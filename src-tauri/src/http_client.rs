@@ -0,0 +1,232 @@
+//! One `reqwest::Client` for every outbound backend request, rebuilt whenever the
+//! frontend syncs network settings (proxy, timeouts, bind address, extra root CAs).
+
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::secure_credentials::read_proxy_password;
+
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 20;
+const DEFAULT_READ_TIMEOUT_SECS: u64 = 45;
+/// Total budget for small API/metadata requests (release lookups, checksum files).
+pub const API_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSettingsPayload {
+    /// `http://`, `https://`, `socks5://` or `socks5h://` proxy URL without credentials.
+    pub proxy_url: Option<String>,
+    /// Proxy user; the password lives in the OS credential store.
+    pub proxy_username: Option<String>,
+    pub connect_timeout_secs: Option<u64>,
+    pub read_timeout_secs: Option<u64>,
+    /// Local IP address outbound connections should bind to.
+    pub bind_address: Option<String>,
+    /// PEM bundle, or a directory of PEM/DER certificates, trusted in addition to the
+    /// built-in roots (corporate TLS-inspecting proxies).
+    pub ca_certificates_path: Option<String>,
+}
+
+#[derive(Default)]
+pub struct HttpClientService {
+    settings: Mutex<NetworkSettingsPayload>,
+    client: Mutex<Option<reqwest::Client>>,
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Proxy URL with the stored credentials folded in, as reqwest, yt-dlp and aria2 expect it.
+fn proxy_url_with_credentials(settings: &NetworkSettingsPayload) -> Result<Option<String>, String> {
+    let Some(raw) = non_empty(&settings.proxy_url) else {
+        return Ok(None);
+    };
+    let mut url =
+        reqwest::Url::parse(raw).map_err(|e| format!("Invalid proxy URL '{}': {}", raw, e))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(format!(
+            "Unsupported proxy scheme '{}' (use http, https, socks5 or socks5h)",
+            url.scheme()
+        ));
+    }
+
+    if let Some(username) = non_empty(&settings.proxy_username) {
+        url.set_username(username)
+            .map_err(|_| "Proxy URL cannot carry credentials".to_string())?;
+        if let Some(password) = read_proxy_password()? {
+            url.set_password(Some(&password))
+                .map_err(|_| "Proxy URL cannot carry credentials".to_string())?;
+        }
+    }
+
+    Ok(Some(url.to_string()))
+}
+
+fn bind_address(settings: &NetworkSettingsPayload) -> Result<Option<IpAddr>, String> {
+    non_empty(&settings.bind_address)
+        .map(|raw| {
+            raw.parse::<IpAddr>()
+                .map_err(|_| format!("Invalid bind address: {}", raw))
+        })
+        .transpose()
+}
+
+fn parse_certificates(path: &Path) -> Result<Vec<reqwest::Certificate>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let parsed = if bytes.windows(10).any(|w| w == b"-----BEGIN") {
+        reqwest::Certificate::from_pem_bundle(&bytes)
+    } else {
+        reqwest::Certificate::from_der(&bytes).map(|cert| vec![cert])
+    };
+    parsed.map_err(|e| format!("Invalid certificate file {}: {}", path.display(), e))
+}
+
+/// Extra trust anchors from a PEM bundle or every `.pem`/`.crt`/`.cer`/`.der` in a directory.
+fn load_extra_root_certificates(raw: &str) -> Result<Vec<reqwest::Certificate>, String> {
+    let path = Path::new(raw);
+    if path.is_file() {
+        return parse_certificates(path);
+    }
+    if !path.is_dir() {
+        return Err(format!("CA certificate path does not exist: {}", raw));
+    }
+
+    let mut files: Vec<_> = fs::read_dir(path)
+        .map_err(|e| format!("Failed to list {}: {}", raw, e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| {
+                        matches!(
+                            ext.to_ascii_lowercase().as_str(),
+                            "pem" | "crt" | "cer" | "der"
+                        )
+                    })
+                    .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+
+    let mut certs = Vec::new();
+    for file in files {
+        certs.extend(parse_certificates(&file)?);
+    }
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", raw));
+    }
+    Ok(certs)
+}
+
+fn build_client(
+    app_handle: &AppHandle,
+    settings: &NetworkSettingsPayload,
+) -> Result<reqwest::Client, String> {
+    let connect_timeout = settings
+        .connect_timeout_secs
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS);
+    let read_timeout = settings
+        .read_timeout_secs
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_READ_TIMEOUT_SECS);
+
+    let mut builder = reqwest::Client::builder()
+        .user_agent(format!("HalalDL/{}", app_handle.package_info().version))
+        .connect_timeout(Duration::from_secs(connect_timeout))
+        .read_timeout(Duration::from_secs(read_timeout));
+
+    if let Some(proxy_url) = proxy_url_with_credentials(settings)? {
+        let proxy = reqwest::Proxy::all(&proxy_url).map_err(|e| format!("Invalid proxy: {}", e))?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(ca_path) = non_empty(&settings.ca_certificates_path) {
+        builder = builder.tls_certs_merge(load_extra_root_certificates(ca_path)?);
+    }
+    if let Some(addr) = bind_address(settings)? {
+        builder = builder.local_address(addr);
+    }
+
+    builder
+        .build()
+        .map_err(|e| format!("HTTP client error: {}", e))
+}
+
+/// The configured client. Cheap to call: `reqwest::Client` is reference-counted.
+pub fn shared_client(app_handle: &AppHandle) -> Result<reqwest::Client, String> {
+    let service = app_handle.state::<HttpClientService>();
+    let mut client = service
+        .client
+        .lock()
+        .map_err(|_| "HTTP client lock poisoned".to_string())?;
+    if let Some(existing) = client.as_ref() {
+        return Ok(existing.clone());
+    }
+
+    let settings = service
+        .settings
+        .lock()
+        .map_err(|_| "Network settings lock poisoned".to_string())?
+        .clone();
+    let built = build_client(app_handle, &settings)?;
+    *client = Some(built.clone());
+    Ok(built)
+}
+
+/// Drop the cached client so the next request picks up changed credentials.
+pub fn invalidate_shared_client(app_handle: &AppHandle) {
+    let service = app_handle.state::<HttpClientService>();
+    if let Ok(mut client) = service.client.lock() {
+        *client = None;
+    };
+}
+
+/// Effective proxy URL (credentials included) for spawned tools, if one is configured.
+/// Fails when the saved password cannot be read rather than silently going direct.
+pub fn configured_proxy_url(app_handle: &AppHandle) -> Result<Option<String>, String> {
+    let service = app_handle.state::<HttpClientService>();
+    let settings = service
+        .settings
+        .lock()
+        .map_err(|_| "Network settings lock poisoned".to_string())?
+        .clone();
+    proxy_url_with_credentials(&settings)
+}
+
+/// Configured local address to connect from, for code that opens its own sockets.
+pub fn configured_bind_address(app_handle: &AppHandle) -> Result<Option<IpAddr>, String> {
+    let service = app_handle.state::<HttpClientService>();
+    let settings = service
+        .settings
+        .lock()
+        .map_err(|_| "Network settings lock poisoned".to_string())?;
+    bind_address(&settings)
+}
+
+#[tauri::command]
+pub fn sync_network_settings(
+    app: AppHandle,
+    payload: NetworkSettingsPayload,
+) -> Result<(), String> {
+    // Build first so invalid settings are rejected without touching the live client.
+    let client = build_client(&app, &payload)?;
+    let service = app.state::<HttpClientService>();
+    *service
+        .settings
+        .lock()
+        .map_err(|_| "Network settings lock poisoned".to_string())? = payload;
+    *service
+        .client
+        .lock()
+        .map_err(|_| "HTTP client lock poisoned".to_string())? = Some(client);
+    Ok(())
+}
@@ -1,6 +1,9 @@
 mod app_bin;
 mod app_paths;
 mod app_update;
+mod archive_limits;
+mod asset_resolver;
+mod checksums;
 mod clipboard;
 mod collection_zip;
 mod diagnostics;
@@ -8,12 +11,30 @@ mod download;
 mod extract;
 mod file_commands;
 mod fs_utils;
+mod http_client;
 mod notifications;
+mod rate_limit;
 mod runtime;
+mod segmented_download;
 mod shell;
 mod secure_credentials;
+mod tls_diagnostics;
+mod tool_backups;
+mod tool_bundle;
+mod tool_integrity;
+mod tool_locks;
+mod tool_manifest;
+mod tool_pins;
+mod tool_preflight;
+mod tool_sources;
+mod tool_specs;
+mod tool_transaction;
+mod tool_version;
 mod tools;
+mod transfers;
+mod update_signature;
 mod version;
+mod ytdlp_install;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -31,6 +52,13 @@ pub fn run() {
     tauri::Builder::default()
         .manage(runtime_state)
         .manage(app_bin::AppBinChildren::default())
+        .manage(http_client::HttpClientService::default())
+        .manage(transfers::TransferRegistry::default())
+        .manage(rate_limit::BandwidthLimiter::default())
+        .manage(tool_manifest::ToolManifestStore::default())
+        .manage(tool_pins::ToolPinStore::default())
+        .manage(tool_integrity::ToolIntegrityState::default())
+        .manage(tool_locks::ToolFileLocks::default())
         .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
             let urls = runtime::capture_launch_urls(&args);
             runtime::append_launch_urls(app, urls);
@@ -47,13 +75,24 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             tools::download_tools,
             tools::stage_manual_tool,
+            tool_bundle::import_tool_bundle,
+            tool_bundle::export_tool_bundle,
+            tool_manifest::get_tool_manifest,
+            tool_pins::get_tool_pins,
+            tool_pins::set_tool_pin,
+            tool_preflight::preflight_tool_update,
+            tool_integrity::scan_tool_integrity,
+            tool_integrity::get_tool_integrity_report,
             shell::add_to_user_path,
             file_commands::write_text_file,
             file_commands::read_text_file,
+            tool_version::probe_tool_version,
+            tool_version::check_tool_version,
             version::fetch_latest_ytdlp_version,
             version::fetch_latest_aria2_version,
             version::fetch_latest_deno_version,
             version::fetch_latest_ffmpeg_version,
+            version::list_available_tool_versions,
             shell::show_in_folder,
             shell::open_path,
             file_commands::delete_file,
@@ -61,8 +100,12 @@ pub fn run() {
             clipboard::copy_files_to_clipboard,
             clipboard::read_text_from_clipboard,
             download::download_url_to_file,
+            segmented_download::download_url_segmented,
             download::post_form_for_text,
             runtime::sync_runtime_settings,
+            http_client::sync_network_settings,
+            rate_limit::sync_bandwidth_limits,
+            tls_diagnostics::inspect_tls_certificate_chain,
             runtime::startup_timings,
             runtime::update_tray_state,
             runtime::restore_main_window,
@@ -87,7 +130,9 @@ pub fn run() {
             tools::resolve_system_tool_paths,
             tools::probe_executable_version,
             tools::upgrade_ytdlp_via_pip,
+            ytdlp_install::detect_ytdlp_install_method,
             tools::update_tool_at_path,
+            transfers::cancel_tool_download,
             tools::list_tool_backups,
             tools::rollback_tool,
             tools::cleanup_tool_backup,
@@ -97,7 +142,10 @@ pub fn run() {
             collection_zip::export_collection_zip,
             secure_credentials::save_ai_api_key,
             secure_credentials::has_ai_api_key,
-            secure_credentials::remove_ai_api_key
+            secure_credentials::remove_ai_api_key,
+            secure_credentials::save_proxy_password,
+            secure_credentials::has_proxy_password,
+            secure_credentials::remove_proxy_password
         ])
         .setup(move |app| {
             use tauri::Manager;
@@ -117,6 +165,12 @@ pub fn run() {
                 runtime::append_launch_urls(&app.handle(), startup_urls.clone());
             }
             runtime::mark_setup_complete(&app.handle());
+            // Undo half-applied tool swaps before anything can start a new one.
+            let recovered = tool_transaction::repair_interrupted(&app.handle());
+            for line in &recovered {
+                eprintln!("[tools] {}", line);
+            }
+            tool_integrity::spawn_startup_scan(app.handle().clone(), recovered);
 
             // Late plugins: not needed before first usable frame / setup metric.
             app.handle()
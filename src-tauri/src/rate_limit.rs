@@ -0,0 +1,117 @@
+//! Token-bucket bandwidth limits for backend HTTP transfers. Every transfer draws from the
+//! global bucket; background ones (startup tool updates) also draw from their own bucket.
+//! Limits can change mid-transfer: waiting streams pick up the new rate on their next slice.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+/// Longest single sleep, so a raised or removed limit takes effect quickly.
+const MAX_WAIT_SLICE: Duration = Duration::from_millis(100);
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthLimitsPayload {
+    /// Cap for all backend transfers combined; `None` or 0 means unlimited.
+    pub max_bytes_per_second: Option<u64>,
+    /// Additional cap for transfers started in the background.
+    pub background_max_bytes_per_second: Option<u64>,
+}
+
+struct BucketState {
+    /// Bytes per second; 0 disables the bucket.
+    rate: u64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+struct TokenBucket {
+    state: Mutex<BucketState>,
+}
+
+impl Default for TokenBucket {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(BucketState {
+                rate: 0,
+                tokens: 0.0,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+}
+
+impl BucketState {
+    /// Refill for the time elapsed, holding at most one second of burst.
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.rate as f64).min(self.rate as f64);
+    }
+}
+
+impl TokenBucket {
+    fn set_rate(&self, rate: u64) {
+        if let Ok(mut state) = self.state.lock() {
+            state.refill(Instant::now());
+            state.rate = rate;
+            state.tokens = state.tokens.min(rate as f64);
+        }
+    }
+
+    async fn acquire(&self, bytes: usize) {
+        let mut remaining = bytes as f64;
+        loop {
+            let wait = {
+                let Ok(mut state) = self.state.lock() else {
+                    return;
+                };
+                state.refill(Instant::now());
+                if state.rate == 0 {
+                    return;
+                }
+                let take = remaining.min(state.tokens.max(0.0));
+                state.tokens -= take;
+                remaining -= take;
+                if remaining <= 0.0 {
+                    return;
+                }
+                let needed = remaining.min(state.rate as f64) / state.rate as f64;
+                Duration::from_secs_f64(needed).min(MAX_WAIT_SLICE)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BandwidthLimiter {
+    global: TokenBucket,
+    background: TokenBucket,
+}
+
+/// Wait until `bytes` may pass under the current limits.
+pub async fn throttle(app_handle: &AppHandle, bytes: usize, background: bool) {
+    let limiter = app_handle.state::<BandwidthLimiter>();
+    if background {
+        limiter.background.acquire(bytes).await;
+    }
+    limiter.global.acquire(bytes).await;
+}
+
+#[tauri::command]
+pub fn sync_bandwidth_limits(
+    app: AppHandle,
+    payload: BandwidthLimitsPayload,
+) -> Result<(), String> {
+    let limiter = app.state::<BandwidthLimiter>();
+    limiter
+        .global
+        .set_rate(payload.max_bytes_per_second.unwrap_or(0));
+    limiter
+        .background
+        .set_rate(payload.background_max_bytes_per_second.unwrap_or(0));
+    Ok(())
+}
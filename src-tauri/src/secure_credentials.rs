@@ -1,6 +1,8 @@
 use keyring::Entry;
 
 const SERVICE: &str = "HalalDL AI";
+const PROXY_SERVICE: &str = "HalalDL Proxy";
+const PROXY_ACCOUNT: &str = "default";
 
 fn entry(profile_id: &str) -> Result<Entry, String> {
     if profile_id.trim().is_empty() || profile_id.len() > 160 {
@@ -33,3 +35,41 @@ pub fn remove_ai_api_key(profile_id: String) -> Result<(), String> {
         Err(error) => Err(format!("Could not remove secure API key: {error}")),
     }
 }
+
+fn proxy_entry() -> Result<Entry, String> {
+    Entry::new(PROXY_SERVICE, PROXY_ACCOUNT).map_err(|error| format!("Windows Credential Manager is unavailable: {error}"))
+}
+
+pub fn read_proxy_password() -> Result<Option<String>, String> {
+    match proxy_entry()?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(error) => Err(format!("Could not read proxy password: {error}")),
+    }
+}
+
+#[tauri::command]
+pub fn save_proxy_password(app: tauri::AppHandle, password: String) -> Result<(), String> {
+    if password.is_empty() {
+        return Err("Proxy password is empty".to_string());
+    }
+    proxy_entry()?.set_password(&password).map_err(|error| format!("Could not save proxy password securely: {error}"))?;
+    crate::http_client::invalidate_shared_client(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn has_proxy_password() -> Result<bool, String> {
+    Ok(read_proxy_password()?.is_some())
+}
+
+#[tauri::command]
+pub fn remove_proxy_password(app: tauri::AppHandle) -> Result<(), String> {
+    match proxy_entry()?.delete_credential() {
+        Ok(_) | Err(keyring::Error::NoEntry) => {
+            crate::http_client::invalidate_shared_client(&app);
+            Ok(())
+        }
+        Err(error) => Err(format!("Could not remove proxy password: {error}")),
+    }
+}
@@ -0,0 +1,396 @@
+//! Multi-connection downloader for direct media URLs (DownloadGram-resolved Instagram media).
+//! Splits the body into byte ranges, fetches them in parallel into one preallocated temp file,
+//! and retries each range on its own. Servers without range support or a validator to pin the
+//! file version fall back to one stream.
+
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::download::{
+    content_range_start, download_url_to_file, emit_transfer_progress, ensure_media_content_type,
+    media_request, resume_validator, sanitize_header_value, BROWSER_USER_AGENT,
+    DEFAULT_ACCEPT_LANGUAGE,
+};
+use crate::fs_utils::temp_path_for;
+use crate::http_client::shared_client;
+use crate::rate_limit::throttle;
+use crate::transfers::{begin_transfer, or_cancelled, transfer_token};
+
+const DEFAULT_CONNECTIONS: usize = 8;
+const MAX_CONNECTIONS: usize = 16;
+const MIN_SEGMENT_BYTES: u64 = 1_048_576;
+const MAX_SEGMENT_RETRIES: u8 = 5;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+struct RemoteFile {
+    total: u64,
+    validator: String,
+}
+
+struct RequestHeaders<'a> {
+    referer: Option<&'a str>,
+    user_agent: &'a str,
+    accept_language: &'a str,
+}
+
+/// Parse the total size out of `Content-Range: bytes 0-0/<total>`.
+fn content_range_total(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let value = headers.get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    let (_, total) = value.rsplit_once('/')?;
+    total.trim().parse().ok()
+}
+
+/// One-byte range request: tells us the size and whether ranges are honoured.
+/// Without an ETag or Last-Modified to send as `If-Range`, segments could be
+/// stitched from two versions of the file, so such servers get one stream.
+async fn probe_remote_file(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &RequestHeaders<'_>,
+    cancel: Option<&CancellationToken>,
+) -> Result<Option<RemoteFile>, String> {
+    let request = media_request(
+        client,
+        url,
+        headers.referer,
+        headers.user_agent,
+        headers.accept_language,
+    )
+    .header(reqwest::header::RANGE, "bytes=0-0")
+    .send();
+    let resp = or_cancelled(cancel, request)
+        .await?
+        .map_err(|e| format!("Download failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {}: {}", resp.status(), url));
+    }
+    ensure_media_content_type(&resp)?;
+    if resp.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Ok(None);
+    }
+
+    let Some(total) = content_range_total(resp.headers()) else {
+        return Ok(None);
+    };
+    let Some(validator) = resume_validator(resp.headers()) else {
+        return Ok(None);
+    };
+
+    Ok(Some(RemoteFile { total, validator }))
+}
+
+fn plan_segments(total: u64, connections: usize) -> Vec<(u64, u64)> {
+    let by_size = (total / MIN_SEGMENT_BYTES).max(1) as usize;
+    let count = connections.clamp(1, MAX_CONNECTIONS).min(by_size);
+    let segment_len = total.div_ceil(count as u64);
+
+    let mut segments = Vec::with_capacity(count);
+    let mut start = 0u64;
+    while start < total {
+        let end = (start + segment_len).min(total) - 1;
+        segments.push((start, end));
+        start = end + 1;
+    }
+    segments
+}
+
+struct SegmentedTransfer<'a> {
+    app_handle: &'a tauri::AppHandle,
+    client: &'a reqwest::Client,
+    url: &'a str,
+    headers: RequestHeaders<'a>,
+    validator: &'a str,
+    temp_path: &'a Path,
+    cancel: Option<&'a CancellationToken>,
+    received: AtomicU64,
+    abort: AtomicBool,
+}
+
+impl SegmentedTransfer<'_> {
+    /// Fetch the inclusive range `[start, end]`, resuming within the segment on retry.
+    /// A segment that gives up aborts its siblings so the whole download fails fast.
+    async fn fetch_segment(&self, range: (u64, u64)) -> Result<(), String> {
+        let result = self.fetch_segment_with_retries(range).await;
+        if result.is_err() {
+            self.abort.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+
+    async fn fetch_segment_with_retries(&self, (start, end): (u64, u64)) -> Result<(), String> {
+        let mut written = 0u64;
+        let expected = end - start + 1;
+        let mut last_error = String::new();
+
+        for attempt in 1..=MAX_SEGMENT_RETRIES {
+            if self.abort.load(Ordering::Relaxed) {
+                return Err("Download aborted".to_string());
+            }
+            if attempt > 1 {
+                tokio::time::sleep(Duration::from_millis(500 * attempt as u64)).await;
+            }
+
+            let request = media_request(
+                self.client,
+                self.url,
+                self.headers.referer,
+                self.headers.user_agent,
+                self.headers.accept_language,
+            )
+            .header(
+                reqwest::header::RANGE,
+                format!("bytes={}-{}", start + written, end),
+            )
+            .header(reqwest::header::IF_RANGE, self.validator)
+            .send();
+
+            let resp = match or_cancelled(self.cancel, request).await? {
+                Ok(resp) => resp,
+                Err(e) => {
+                    last_error = e.to_string();
+                    continue;
+                }
+            };
+            if resp.status() == reqwest::StatusCode::OK {
+                // If-Range mismatch: the file changed under us, segments can't be stitched.
+                return Err("Remote file changed during download".to_string());
+            }
+            if resp.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                last_error = format!("HTTP {}", resp.status());
+                continue;
+            }
+            // Writing a range that starts elsewhere would corrupt the segment.
+            let range_start = content_range_start(resp.headers());
+            if range_start != Some(start + written) {
+                last_error = format!(
+                    "Server sent range starting at {:?}, expected {}",
+                    range_start,
+                    start + written
+                );
+                continue;
+            }
+
+            let mut file = tokio::fs::OpenOptions::new()
+                .write(true)
+                .open(self.temp_path)
+                .await
+                .map_err(|e| format!("File open failed: {}", e))?;
+            file.seek(std::io::SeekFrom::Start(start + written))
+                .await
+                .map_err(|e| format!("File seek failed: {}", e))?;
+
+            let mut stream = resp.bytes_stream();
+            let mut failed = false;
+            while let Some(chunk) = or_cancelled(self.cancel, stream.next()).await? {
+                if self.abort.load(Ordering::Relaxed) {
+                    return Err("Download aborted".to_string());
+                }
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        last_error = e.to_string();
+                        failed = true;
+                        break;
+                    }
+                };
+                let room = (expected - written) as usize;
+                let slice = &chunk[..chunk.len().min(room)];
+                throttle(self.app_handle, slice.len(), false).await;
+                file.write_all(slice)
+                    .await
+                    .map_err(|e| format!("File write failed: {}", e))?;
+                written += slice.len() as u64;
+                self.received
+                    .fetch_add(slice.len() as u64, Ordering::Relaxed);
+                if written >= expected {
+                    break;
+                }
+            }
+            file.flush()
+                .await
+                .map_err(|e| format!("File flush failed: {}", e))?;
+
+            if written >= expected {
+                return Ok(());
+            }
+            if !failed {
+                last_error = "Connection closed before the segment finished".to_string();
+            }
+        }
+
+        Err(format!(
+            "Segment {}-{} failed after {} attempts: {}",
+            start, end, MAX_SEGMENT_RETRIES, last_error
+        ))
+    }
+}
+
+async fn report_progress(
+    app_handle: &tauri::AppHandle,
+    progress_id: &str,
+    total: u64,
+    received: &AtomicU64,
+    finished: &AtomicBool,
+) {
+    let started = Instant::now();
+    let mut last_tick = started;
+    let mut last_bytes = 0u64;
+    let mut speed = 0f64;
+
+    while !finished.load(Ordering::Relaxed) {
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+        let now = Instant::now();
+        let bytes = received.load(Ordering::Relaxed);
+        let elapsed = now.duration_since(last_tick).as_secs_f64();
+        if elapsed > 0.0 {
+            let sample = bytes.saturating_sub(last_bytes) as f64 / elapsed;
+            // Smooth out per-tick jitter so the ETA does not jump around.
+            speed = if speed == 0.0 {
+                sample
+            } else {
+                speed * 0.7 + sample * 0.3
+            };
+        }
+        last_tick = now;
+        last_bytes = bytes;
+        emit_transfer_progress(app_handle, progress_id, bytes, total, speed as u64);
+    }
+
+    let elapsed = started.elapsed().as_secs_f64().max(0.001);
+    let bytes = received.load(Ordering::Relaxed);
+    emit_transfer_progress(
+        app_handle,
+        progress_id,
+        bytes,
+        total,
+        (bytes as f64 / elapsed) as u64,
+    );
+}
+
+fn activate_download(temp_path: &Path, dest_path: &Path) -> Result<(), String> {
+    if dest_path.exists() {
+        fs::remove_file(dest_path).map_err(|e| format!("Failed to replace file: {}", e))?;
+    }
+    fs::rename(temp_path, dest_path).map_err(|e| format!("Failed to move download: {}", e))
+}
+
+/// Download a direct media URL over several connections. Emits `download-progress`
+/// events keyed by `progress_id` with aggregate bytes, speed and ETA; the same id
+/// cancels the transfer through `cancel_tool_download`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn download_url_segmented(
+    app_handle: tauri::AppHandle,
+    url: String,
+    dest: String,
+    connections: Option<usize>,
+    progress_id: Option<String>,
+    referer: Option<String>,
+    user_agent: Option<String>,
+    accept_language: Option<String>,
+) -> Result<String, String> {
+    let user_agent = sanitize_header_value(user_agent, BROWSER_USER_AGENT);
+    let accept_language = sanitize_header_value(accept_language, DEFAULT_ACCEPT_LANGUAGE);
+    let headers = RequestHeaders {
+        referer: referer.as_deref(),
+        user_agent: &user_agent,
+        accept_language: &accept_language,
+    };
+    let client = shared_client(&app_handle)?;
+    let progress_id = progress_id.unwrap_or_else(|| url.clone());
+    let _transfer = begin_transfer(&app_handle, &progress_id, false);
+    let cancel = transfer_token(&app_handle, &progress_id);
+
+    let remote = probe_remote_file(&client, &url, &headers, cancel.as_ref()).await?;
+    let segments = remote
+        .as_ref()
+        .map(|r| plan_segments(r.total, connections.unwrap_or(DEFAULT_CONNECTIONS)))
+        .unwrap_or_default();
+    let Some(remote) = remote.filter(|_| segments.len() > 1) else {
+        let single = download_url_to_file(
+            app_handle.clone(),
+            url.clone(),
+            dest.clone(),
+            referer.clone(),
+            Some(user_agent.clone()),
+            Some(accept_language.clone()),
+        );
+        return or_cancelled(cancel.as_ref(), single).await?;
+    };
+
+    let dest_path = Path::new(&dest);
+    if let Some(parent) = dest_path.parent() {
+        if !parent.exists() {
+            fs::create_dir_all(parent).map_err(|e| format!("mkdir failed: {}", e))?;
+        }
+    }
+    let temp_path = temp_path_for(dest_path)?;
+    {
+        let file =
+            fs::File::create(&temp_path).map_err(|e| format!("File create failed: {}", e))?;
+        file.set_len(remote.total)
+            .map_err(|e| format!("Failed to preallocate {} bytes: {}", remote.total, e))?;
+    }
+
+    let transfer = SegmentedTransfer {
+        app_handle: &app_handle,
+        client: &client,
+        url: &url,
+        headers,
+        validator: &remote.validator,
+        temp_path: &temp_path,
+        cancel: cancel.as_ref(),
+        received: AtomicU64::new(0),
+        abort: AtomicBool::new(false),
+    };
+    let finished = AtomicBool::new(false);
+
+    let fetch_all = async {
+        let results = futures_util::future::join_all(
+            segments
+                .iter()
+                .map(|&segment| transfer.fetch_segment(segment)),
+        )
+        .await;
+        finished.store(true, Ordering::Relaxed);
+        results
+    };
+    let (results, ()) = futures_util::future::join(
+        fetch_all,
+        report_progress(
+            &app_handle,
+            &progress_id,
+            remote.total,
+            &transfer.received,
+            &finished,
+        ),
+    )
+    .await;
+
+    if let Some(err) = results.into_iter().find_map(Result::err) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err);
+    }
+
+    let received = transfer.received.load(Ordering::Relaxed);
+    let written = fs::metadata(&temp_path)
+        .map_err(|e| format!("Failed to read download: {}", e))?
+        .len();
+    if written != remote.total || received != remote.total {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!(
+            "Download incomplete ({} of {} bytes)",
+            received, remote.total
+        ));
+    }
+
+    activate_download(&temp_path, dest_path)?;
+    Ok(dest)
+}
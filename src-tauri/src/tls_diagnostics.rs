@@ -0,0 +1,376 @@
+//! Certificate chain inspection for "TLS handshake failed" support cases: shows what a host
+//! (or the TLS-inspecting proxy in front of it) actually presents, and whether the shared
+//! client, with any custom root CAs, trusts it.
+
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::WebPkiSupportedAlgorithms;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpSocket, TcpStream};
+use x509_parser::extensions::GeneralName;
+
+use crate::http_client::{
+    configured_bind_address, configured_proxy_url, shared_client, API_REQUEST_TIMEOUT,
+};
+
+const DEFAULT_TLS_PORT: u16 = 443;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub serial: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub currently_valid: bool,
+    pub self_signed: bool,
+    pub dns_names: Vec<String>,
+    pub sha256_fingerprint: String,
+    pub pem: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsChainReport {
+    pub host: String,
+    pub port: u16,
+    pub via_proxy: bool,
+    pub protocol_version: Option<String>,
+    pub cipher_suite: Option<String>,
+    /// Leaf first, in the order the server sent them.
+    pub certificates: Vec<CertificateInfo>,
+    /// Whether the shared HTTP client (built-in plus custom roots) accepts the chain.
+    pub trusted: bool,
+    pub trust_error: Option<String>,
+}
+
+/// Accepts any chain so it can be recorded; handshake signatures are still checked so the
+/// captured chain really belongs to the peer we talked to.
+#[derive(Debug)]
+struct CapturingVerifier {
+    algorithms: WebPkiSupportedAlgorithms,
+    chain: Mutex<Vec<CertificateDer<'static>>>,
+}
+
+impl ServerCertVerifier for CapturingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if let Ok(mut chain) = self.chain.lock() {
+            *chain = std::iter::once(end_entity)
+                .chain(intermediates)
+                .map(|cert| cert.clone().into_owned())
+                .collect();
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Connect from the configured bind address, like the shared client does. Only
+/// resolved addresses of the same family as the bind address are tried.
+async fn connect_tcp(host: &str, port: u16, bind: Option<IpAddr>) -> std::io::Result<TcpStream> {
+    let Some(bind) = bind else {
+        return TcpStream::connect((host, port)).await;
+    };
+    let mut last_error = None;
+    for addr in tokio::net::lookup_host((host, port)).await? {
+        if addr.is_ipv4() != bind.is_ipv4() {
+            continue;
+        }
+        let socket = if addr.is_ipv4() {
+            TcpSocket::new_v4()?
+        } else {
+            TcpSocket::new_v6()?
+        };
+        socket.bind((bind, 0).into())?;
+        match socket.connect(addr).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::AddrNotAvailable,
+            format!("{} has no address reachable from {}", host, bind),
+        )
+    }))
+}
+
+/// Open a TCP stream to the target through an HTTP CONNECT tunnel.
+async fn connect_via_http_proxy(
+    proxy: &reqwest::Url,
+    host: &str,
+    port: u16,
+    bind: Option<IpAddr>,
+) -> Result<TcpStream, String> {
+    let proxy_host = proxy
+        .host_str()
+        .ok_or_else(|| "Proxy URL has no host".to_string())?;
+    let proxy_port = proxy.port_or_known_default().unwrap_or(8080);
+    let mut stream = connect_tcp(proxy_host, proxy_port, bind)
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to connect to proxy {}:{}: {}",
+                proxy_host, proxy_port, e
+            )
+        })?;
+
+    let mut request = format!(
+        "CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n",
+        host = host,
+        port = port
+    );
+    if !proxy.username().is_empty() {
+        let credentials = format!(
+            "{}:{}",
+            percent_decode(proxy.username()),
+            percent_decode(proxy.password().unwrap_or(""))
+        );
+        request.push_str(&format!(
+            "Proxy-Authorization: Basic {}\r\n",
+            base64::engine::general_purpose::STANDARD.encode(credentials)
+        ));
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("Proxy write failed: {}", e))?;
+
+    // Read the response head byte by byte so no TLS bytes are consumed past it.
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() > 8192 {
+            return Err("Proxy sent an oversized CONNECT response".to_string());
+        }
+        let byte = stream
+            .read_u8()
+            .await
+            .map_err(|e| format!("Proxy read failed: {}", e))?;
+        head.push(byte);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let status_line = head.lines().next().unwrap_or("");
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("Proxy refused CONNECT: {}", status_line.trim()));
+    }
+    Ok(stream)
+}
+
+fn to_pem(der: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(der);
+    let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str("-----END CERTIFICATE-----\n");
+    pem
+}
+
+fn describe_certificate(der: &CertificateDer<'_>) -> Result<CertificateInfo, String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der.as_ref())
+        .map_err(|e| format!("Failed to parse certificate: {}", e))?;
+
+    let dns_names = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|san| {
+            san.value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(dns) => Some(dns.to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let fingerprint = Sha256::digest(der.as_ref())
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":");
+
+    Ok(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        serial: cert.raw_serial_as_string(),
+        not_before: cert.validity().not_before.to_string(),
+        not_after: cert.validity().not_after.to_string(),
+        currently_valid: cert.validity().is_valid(),
+        self_signed: cert.subject() == cert.issuer(),
+        dns_names,
+        sha256_fingerprint: fingerprint,
+        pem: to_pem(der.as_ref()),
+    })
+}
+
+/// Ask the shared client to talk to the host; any HTTP response means the chain was trusted.
+async fn check_trust(app_handle: &tauri::AppHandle, host: &str, port: u16) -> Option<String> {
+    let client = match shared_client(app_handle) {
+        Ok(client) => client,
+        Err(e) => return Some(e),
+    };
+    let url = format!("https://{}:{}/", host, port);
+    match client.head(&url).timeout(API_REQUEST_TIMEOUT).send().await {
+        Ok(_) => None,
+        Err(e) => {
+            // reqwest's Display stops at the top-level error; the TLS reason sits below it.
+            let mut message = e.to_string();
+            let mut source = std::error::Error::source(&e);
+            while let Some(inner) = source {
+                message.push_str(&format!(": {}", inner));
+                source = inner.source();
+            }
+            Some(message)
+        }
+    }
+}
+
+/// Show the certificate chain a host presents, through the configured HTTP proxy if any.
+#[tauri::command]
+pub async fn inspect_tls_certificate_chain(
+    app_handle: tauri::AppHandle,
+    host: String,
+    port: Option<u16>,
+) -> Result<TlsChainReport, String> {
+    let host = host.trim().to_string();
+    if host.is_empty() {
+        return Err("Host is empty".to_string());
+    }
+    let port = port.unwrap_or(DEFAULT_TLS_PORT);
+
+    let proxy = configured_proxy_url(&app_handle)?
+        .map(|raw| reqwest::Url::parse(&raw).map_err(|e| format!("Invalid proxy URL: {}", e)))
+        .transpose()?;
+    let bind = configured_bind_address(&app_handle)?;
+    // A silent proxy or firewall must not leave the diagnostic hanging.
+    let stream = match &proxy {
+        Some(proxy) if proxy.scheme() == "http" => tokio::time::timeout(
+            API_REQUEST_TIMEOUT,
+            connect_via_http_proxy(proxy, &host, port, bind),
+        )
+        .await
+        .map_err(|_| format!("Proxy tunnel to {}:{} timed out", host, port))??,
+        Some(proxy) => {
+            return Err(format!(
+                "Chain inspection supports direct connections and http:// proxies, not {}://",
+                proxy.scheme()
+            ))
+        }
+        None => tokio::time::timeout(API_REQUEST_TIMEOUT, connect_tcp(&host, port, bind))
+            .await
+            .map_err(|_| format!("Connecting to {}:{} timed out", host, port))?
+            .map_err(|e| format!("Failed to connect to {}:{}: {}", host, port, e))?,
+    };
+
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let verifier = Arc::new(CapturingVerifier {
+        algorithms: provider.signature_verification_algorithms,
+        chain: Mutex::new(Vec::new()),
+    });
+    let config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("TLS setup failed: {}", e))?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+    let server_name = ServerName::try_from(host.clone())
+        .map_err(|e| format!("Invalid host '{}': {}", host, e))?;
+
+    let tls = tokio::time::timeout(
+        API_REQUEST_TIMEOUT,
+        tokio_rustls::TlsConnector::from(Arc::new(config)).connect(server_name, stream),
+    )
+    .await
+    .map_err(|_| format!("TLS handshake with {}:{} timed out", host, port))?
+    .map_err(|e| format!("TLS handshake with {}:{} failed: {}", host, port, e))?;
+    let (_, connection) = tls.get_ref();
+    let protocol_version = connection.protocol_version().map(|v| format!("{:?}", v));
+    let cipher_suite = connection
+        .negotiated_cipher_suite()
+        .map(|suite| format!("{:?}", suite.suite()));
+    drop(tls);
+
+    let chain = verifier
+        .chain
+        .lock()
+        .map_err(|_| "Certificate capture lock poisoned".to_string())?
+        .clone();
+    let certificates = chain
+        .iter()
+        .map(describe_certificate)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let trust_error = check_trust(&app_handle, &host, port).await;
+    Ok(TlsChainReport {
+        host,
+        port,
+        via_proxy: proxy.is_some(),
+        protocol_version,
+        cipher_suite,
+        certificates,
+        trusted: trust_error.is_none(),
+        trust_error,
+    })
+}
@@ -0,0 +1,287 @@
+//! Versioned backups of app-managed tools in `bin/backups/<tool>/<version>/`. After each
+//! install the `.old` files it left behind are moved into a new generation, so a second
+//! update no longer overwrites the last good build.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fs_utils::backup_path_for;
+use crate::tool_manifest::{now_ms, ToolManifestEntry};
+use crate::tool_specs::{tool_spec, tool_specs};
+use crate::tool_transaction::{replace_all, Replacement};
+
+pub const BACKUPS_DIR: &str = "backups";
+const GENERATION_FILE: &str = "backup.json";
+/// Generations kept per tool.
+const MAX_GENERATIONS: usize = 3;
+/// Older generations are pruned too, except the newest one.
+const MAX_GENERATION_AGE_MS: u64 = 90 * 24 * 60 * 60 * 1000;
+
+/// Contents of `backup.json` in a generation directory.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationInfo {
+    tool: String,
+    version: String,
+    /// Milliseconds since the Unix epoch.
+    created_at: u64,
+    /// Manifest entry of the install this generation preserves, if it was recorded.
+    entry: Option<ToolManifestEntry>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolBackup {
+    pub tool: String,
+    /// `None` for a single `.old` backup next to the binary (system paths, older installs).
+    pub version: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+    pub path: String,
+}
+
+/// A generation swapped back into the bin dir.
+pub struct RestoredGeneration {
+    pub version: String,
+    pub entry: Option<ToolManifestEntry>,
+    pub files: Vec<String>,
+}
+
+fn tool_backups_dir(bin_dir: &Path, tool: &str) -> PathBuf {
+    bin_dir.join(BACKUPS_DIR).join(tool)
+}
+
+/// Directory-safe label for a probed version line, e.g. `deno 2.1.4 (stable, ...)` -> `2.1.4`.
+fn version_label(version: Option<&str>, created_at: u64) -> String {
+    let token = version.and_then(|line| {
+        line.split_whitespace()
+            .find(|word| {
+                word.chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_digit() || c == 'n')
+            })
+            .filter(|word| word.chars().any(|c| c.is_ascii_digit()))
+    });
+    let label: String = token
+        .unwrap_or("")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(64)
+        .collect();
+    if label.is_empty() {
+        format!("unknown-{}", created_at)
+    } else {
+        label
+    }
+}
+
+fn read_generation(dir: &Path) -> Option<GenerationInfo> {
+    let text = fs::read_to_string(dir.join(GENERATION_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Generations of `tool`, newest first.
+fn list_generations(bin_dir: &Path, tool: &str) -> Vec<(PathBuf, GenerationInfo)> {
+    let mut generations: Vec<(PathBuf, GenerationInfo)> =
+        match fs::read_dir(tool_backups_dir(bin_dir, tool)) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .filter_map(|path| read_generation(&path).map(|info| (path, info)))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+    generations.sort_by_key(|(_, info)| std::cmp::Reverse(info.created_at));
+    generations
+}
+
+/// Apply the retention policy to `tool`'s generations.
+fn prune_generations(bin_dir: &Path, tool: &str) {
+    let now = now_ms();
+    for (index, (path, info)) in list_generations(bin_dir, tool).into_iter().enumerate() {
+        let expired = index > 0 && now.saturating_sub(info.created_at) > MAX_GENERATION_AGE_MS;
+        if index >= MAX_GENERATIONS || expired {
+            if let Err(e) = fs::remove_dir_all(&path) {
+                eprintln!(
+                    "[tools] Warning: failed to prune backup {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Move the `.old` files an install of `tool` left in `bin_dir` into a new generation
+/// labelled with the version of `replaced`, then prune old generations. Libraries of
+/// `replaced` that the new install (with `libraries`) no longer ships go along with them.
+pub fn archive_replaced(
+    bin_dir: &Path,
+    tool: &str,
+    replaced: Option<ToolManifestEntry>,
+    libraries: &[String],
+) -> Result<Option<ToolBackup>, String> {
+    let old_libraries = replaced
+        .as_ref()
+        .map(|entry| entry.libraries.as_slice())
+        .unwrap_or_default();
+    let mut backups = Vec::new();
+    for name in tool_spec(tool)?
+        .binaries()
+        .iter()
+        .copied()
+        .chain(old_libraries.iter().map(String::as_str))
+    {
+        let backup = backup_path_for(&bin_dir.join(name))?;
+        if backup.is_file() {
+            backups.push((name.to_string(), backup));
+        }
+    }
+    for name in old_libraries {
+        let stale = bin_dir.join(name);
+        let kept = libraries.iter().any(|l| l.eq_ignore_ascii_case(name));
+        if !kept && stale.is_file() && !backups.iter().any(|(n, _)| n == name) {
+            backups.push((name.clone(), stale));
+        }
+    }
+    if backups.is_empty() {
+        return Ok(None);
+    }
+
+    let created_at = now_ms();
+    let version = version_label(
+        replaced.as_ref().and_then(|entry| entry.version.as_deref()),
+        created_at,
+    );
+    let dir = tool_backups_dir(bin_dir, tool).join(&version);
+    if dir.exists() {
+        // Same version backed up again; the newer copy wins.
+        fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to replace backup {}: {}", dir.display(), e))?;
+    }
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create backup {}: {}", dir.display(), e))?;
+
+    for (name, backup) in backups {
+        fs::rename(&backup, dir.join(&name))
+            .map_err(|e| format!("Failed to move {} into {}: {}", name, dir.display(), e))?;
+    }
+    let info = GenerationInfo {
+        tool: tool.to_string(),
+        version: version.clone(),
+        created_at,
+        entry: replaced,
+    };
+    let json = serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?;
+    fs::write(dir.join(GENERATION_FILE), json)
+        .map_err(|e| format!("Failed to write {}: {}", GENERATION_FILE, e))?;
+
+    prune_generations(bin_dir, tool);
+    Ok(Some(ToolBackup {
+        tool: tool.to_string(),
+        version: Some(version),
+        created_at,
+        path: dir.to_string_lossy().to_string(),
+    }))
+}
+
+/// Every generation in `bin_dir`, grouped by tool and newest first.
+pub fn list_generation_backups(bin_dir: &Path) -> Vec<ToolBackup> {
+    tool_specs()
+        .iter()
+        .flat_map(|spec| list_generations(bin_dir, spec.id()))
+        .map(|(path, info)| ToolBackup {
+            tool: info.tool,
+            version: Some(info.version),
+            created_at: info.created_at,
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+/// Swap generation `version` (the newest when `None`) of `tool` back into `bin_dir`, all
+/// binaries and libraries at once. Returns `Ok(None)` when the tool has no generations at all.
+pub fn restore_generation(
+    app_handle: &tauri::AppHandle,
+    bin_dir: &Path,
+    tool: &str,
+    version: Option<&str>,
+) -> Result<Option<RestoredGeneration>, String> {
+    let generations = list_generations(bin_dir, tool);
+    let Some((dir, info)) = (match version {
+        Some(version) => generations
+            .into_iter()
+            .find(|(_, info)| info.version == version),
+        None => generations.into_iter().next(),
+    }) else {
+        return match version {
+            Some(version) => Err(format!("No {} backup for version {}", tool, version)),
+            None => Ok(None),
+        };
+    };
+
+    let spec = tool_spec(tool)?;
+    let mut files = Vec::new();
+    let mut replacements = Vec::new();
+    for saved in fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read backup {}: {}", dir.display(), e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+    {
+        let Some(name) = saved.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if name == GENERATION_FILE {
+            continue;
+        }
+        replacements.push(Replacement {
+            dest: bin_dir.join(&name),
+            incoming: saved,
+        });
+        files.push(name);
+    }
+    if !files.iter().any(|name| spec.owns_binary(name)) {
+        return Err(format!(
+            "Backup {} of {} has no binaries",
+            info.version, tool
+        ));
+    }
+    replace_all(app_handle, tool, replacements, false)?;
+    if let Err(e) = fs::remove_dir_all(&dir) {
+        eprintln!(
+            "[tools] Warning: failed to remove restored backup {}: {}",
+            dir.display(),
+            e
+        );
+    }
+
+    Ok(Some(RestoredGeneration {
+        version: info.version,
+        entry: info.entry,
+        files,
+    }))
+}
+
+/// Delete every generation of `tool`; returns how many were removed.
+pub fn remove_generations(bin_dir: &Path, tool: &str) -> Result<usize, String> {
+    let generations = list_generations(bin_dir, tool);
+    for (path, _) in &generations {
+        fs::remove_dir_all(path)
+            .map_err(|e| format!("Failed to remove backup {}: {}", path.display(), e))?;
+    }
+    let tool_dir = tool_backups_dir(bin_dir, tool);
+    if tool_dir.exists() {
+        let _ = fs::remove_dir(&tool_dir);
+    }
+    Ok(generations.len())
+}
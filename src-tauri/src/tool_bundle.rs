@@ -0,0 +1,511 @@
+//! Offline tool bundles: a zip of app-managed tool binaries plus a manifest with versions and
+//! SHA-256 values, for moving tools onto machines without internet access.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::archive_limits::{ensure_disk_space, ExtractionBudget};
+use crate::download::{emit_progress, sha256_of_path};
+use crate::fs_utils::{make_executable, temp_path_for};
+use crate::tool_locks::try_lock_tool_files;
+use crate::tool_manifest::{recorded_entry, InstallMethod, ToolManifestEntry};
+use crate::tool_specs::{tool_spec, tool_specs};
+use crate::tool_transaction::{replace_all, Replacement};
+use crate::tools::finish_install;
+
+/// Bundle layout version this build reads and writes.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "halaldl-tools.json";
+/// A manifest lists a handful of tools; anything bigger is not one of ours.
+const MAX_MANIFEST_SIZE: u64 = 1024 * 1024;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolBundleFile {
+    pub name: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolBundleEntry {
+    pub id: String,
+    /// First line of the tool's version output when it was exported.
+    pub version: Option<String>,
+    pub files: Vec<ToolBundleFile>,
+    /// Shared libraries the install placed next to the binaries (FFmpeg `shared` DLLs).
+    #[serde(default)]
+    pub libraries: Vec<ToolBundleFile>,
+}
+
+impl ToolBundleEntry {
+    /// Binaries followed by libraries, in the order they are written to the bundle.
+    fn bundled_files(&self) -> impl Iterator<Item = &ToolBundleFile> {
+        self.files.iter().chain(&self.libraries)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolBundleManifest {
+    pub format_version: u32,
+    /// `std::env::consts::OS` / `ARCH` of the exporting machine.
+    pub os: String,
+    pub arch: String,
+    pub tools: Vec<ToolBundleEntry>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolBundleResult {
+    pub path: String,
+    pub tools: Vec<ToolBundleEntry>,
+}
+
+fn bundle_file(dir: &Path, name: &str) -> Result<ToolBundleFile, String> {
+    let path = dir.join(name);
+    let size = fs::metadata(&path)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?
+        .len();
+    Ok(ToolBundleFile {
+        name: name.to_string(),
+        sha256: sha256_of_path(&path)?,
+        size,
+    })
+}
+
+/// Zip entry path of a bundled binary.
+fn entry_name(tool: &str, file_name: &str) -> String {
+    format!("{}/{}", tool, file_name)
+}
+
+fn write_bundle(
+    output: &Path,
+    bin_dir: &Path,
+    manifest: &ToolBundleManifest,
+) -> Result<(), String> {
+    let file = fs::File::create(output).map_err(|e| format!("Failed to create bundle: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::FileOptions::<()>::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+
+    for entry in &manifest.tools {
+        for bundled in entry.bundled_files() {
+            zip.start_file(entry_name(&entry.id, &bundled.name), options)
+                .map_err(|e| format!("Could not add {} to bundle: {}", bundled.name, e))?;
+            let mut input = fs::File::open(bin_dir.join(&bundled.name))
+                .map_err(|e| format!("Failed to read {}: {}", bundled.name, e))?;
+            std::io::copy(&mut input, &mut zip)
+                .map_err(|e| format!("Could not write {} to bundle: {}", bundled.name, e))?;
+        }
+    }
+
+    let manifest_json = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+    zip.start_file(MANIFEST_NAME, options.unix_permissions(0o644))
+        .map_err(|e| format!("Could not add manifest to bundle: {}", e))?;
+    zip.write_all(&manifest_json)
+        .map_err(|e| format!("Could not write manifest to bundle: {}", e))?;
+    zip.finish()
+        .map_err(|e| format!("Could not finish bundle: {}", e))?;
+    Ok(())
+}
+
+fn export_bundle(
+    app_handle: &tauri::AppHandle,
+    output_path: &str,
+    tools: Option<Vec<String>>,
+) -> Result<ToolBundleResult, String> {
+    if output_path.trim().is_empty() {
+        return Err("Output path is empty".to_string());
+    }
+    let paths = crate::app_paths::ensure_app_dirs(app_handle)?;
+    let bin_dir = PathBuf::from(&paths.bin_dir);
+
+    let explicit = tools.is_some();
+    let requested = tools.unwrap_or_else(|| {
+        tool_specs()
+            .iter()
+            .map(|spec| spec.id().to_string())
+            .collect()
+    });
+
+    let mut entries = Vec::new();
+    for tool in &requested {
+        let spec = tool_spec(tool)?;
+        // Import needs every binary (ffmpeg and ffprobe) and recorded library, so a partial
+        // install is not exported.
+        let primary = bin_dir.join(spec.primary_binary());
+        let libraries = recorded_entry(app_handle, tool, &bin_dir)
+            .map(|entry| entry.libraries)
+            .unwrap_or_default();
+        if !spec
+            .binaries()
+            .iter()
+            .copied()
+            .chain(libraries.iter().map(String::as_str))
+            .all(|name| bin_dir.join(name).is_file())
+        {
+            if explicit {
+                return Err(format!(
+                    "{} is not installed in {}",
+                    tool,
+                    bin_dir.display()
+                ));
+            }
+            continue;
+        }
+
+        emit_progress(app_handle, tool, 0.0, "Hashing for offline bundle...");
+        let files = spec
+            .binaries()
+            .iter()
+            .map(|name| bundle_file(&bin_dir, name))
+            .collect::<Result<_, _>>()?;
+        let libraries = libraries
+            .iter()
+            .map(|name| bundle_file(&bin_dir, name))
+            .collect::<Result<_, _>>()?;
+        entries.push(ToolBundleEntry {
+            id: tool.clone(),
+            version: spec.probe_version(&primary),
+            files,
+            libraries,
+        });
+    }
+
+    if entries.is_empty() {
+        return Err("No app-managed tools are installed to export".to_string());
+    }
+
+    let manifest = ToolBundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        tools: entries,
+    };
+
+    // Build next to the target so an interrupted export never leaves a truncated bundle.
+    let output = PathBuf::from(output_path.trim());
+    let temp = temp_path_for(&output)?;
+    if let Err(e) = write_bundle(&temp, &bin_dir, &manifest) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    if output.exists() {
+        let _ = fs::remove_file(&output);
+    }
+    fs::rename(&temp, &output).map_err(|e| format!("Failed to finalize bundle: {}", e))?;
+
+    Ok(ToolBundleResult {
+        path: output.to_string_lossy().to_string(),
+        tools: manifest.tools,
+    })
+}
+
+fn read_manifest(archive: &mut zip::ZipArchive<fs::File>) -> Result<ToolBundleManifest, String> {
+    let file = archive
+        .by_name(MANIFEST_NAME)
+        .map_err(|_| format!("Bundle has no {}", MANIFEST_NAME))?;
+    // The declared size can lie, so the read itself is capped too.
+    let mut text = String::new();
+    file.take(MAX_MANIFEST_SIZE + 1)
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Failed to read {}: {}", MANIFEST_NAME, e))?;
+    if text.len() as u64 > MAX_MANIFEST_SIZE {
+        return Err(format!(
+            "{} is larger than {} bytes",
+            MANIFEST_NAME, MAX_MANIFEST_SIZE
+        ));
+    }
+    let manifest: ToolBundleManifest =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", MANIFEST_NAME, e))?;
+
+    if manifest.format_version != BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "Bundle format version {} is not supported (expected {})",
+            manifest.format_version, BUNDLE_FORMAT_VERSION
+        ));
+    }
+    if manifest.os != std::env::consts::OS || manifest.arch != std::env::consts::ARCH {
+        return Err(format!(
+            "Bundle was exported for {}/{} but this machine is {}/{}",
+            manifest.os,
+            manifest.arch,
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ));
+    }
+    Ok(manifest)
+}
+
+/// Reject bundles whose entries and manifest disagree, before anything is extracted.
+fn check_entries(
+    archive: &zip::ZipArchive<fs::File>,
+    manifest: &ToolBundleManifest,
+) -> Result<(), String> {
+    let mut listed = vec![MANIFEST_NAME.to_string()];
+    for entry in &manifest.tools {
+        let binaries = tool_spec(&entry.id)?.binaries();
+        for &binary in binaries {
+            if !entry.files.iter().any(|bundled| bundled.name == binary) {
+                return Err(format!("Bundle has no {} for {}", binary, entry.id));
+            }
+        }
+        for bundled in &entry.files {
+            if !binaries.contains(&bundled.name.as_str()) {
+                return Err(format!(
+                    "Manifest lists {} for {}, which is not one of its binaries",
+                    bundled.name, entry.id
+                ));
+            }
+            listed.push(entry_name(&entry.id, &bundled.name));
+        }
+        for library in &entry.libraries {
+            // Plain file names only: a library must land next to the binaries, nowhere else.
+            let plain = Path::new(&library.name).file_name()
+                == Some(std::ffi::OsStr::new(&library.name))
+                && !library.name.contains(['/', '\\']);
+            if !plain || binaries.contains(&library.name.as_str()) {
+                return Err(format!(
+                    "Manifest lists {} as a library of {}, which is not allowed",
+                    library.name, entry.id
+                ));
+            }
+            listed.push(entry_name(&entry.id, &library.name));
+        }
+    }
+
+    let names: Vec<&str> = archive.file_names().collect();
+    for name in &names {
+        if name.ends_with('/') {
+            continue;
+        }
+        if !listed.iter().any(|listed| listed == name) {
+            return Err(format!(
+                "Bundle entry {} is not listed in the manifest",
+                name
+            ));
+        }
+    }
+    for name in &listed {
+        if !names.contains(&name.as_str()) {
+            return Err(format!("Bundle is missing {}", name));
+        }
+    }
+    Ok(())
+}
+
+/// Hold the manifest sizes of the files about to be extracted to the archive limits, and
+/// make sure they fit next to the installed tools.
+fn check_sizes(
+    archive: &mut zip::ZipArchive<fs::File>,
+    bundle_path: &Path,
+    bin_dir: &Path,
+    selected: &[ToolBundleEntry],
+) -> Result<(), String> {
+    let mut budget = ExtractionBudget::new(bundle_path, bin_dir);
+    let mut needed = 0u64;
+    for entry in selected {
+        for bundled in entry.bundled_files() {
+            let name = entry_name(&entry.id, &bundled.name);
+            let index = archive
+                .index_for_name(&name)
+                .ok_or_else(|| format!("Bundle is missing {}", name))?;
+            let packed = archive
+                .by_index_raw(index)
+                .map_err(|e| format!("Failed to open {}: {}", name, e))?
+                .compressed_size();
+            budget.check_entry(&name, bundled.size, Some(packed))?;
+            needed = needed.saturating_add(bundled.size);
+        }
+    }
+    ensure_disk_space(bin_dir, needed)?;
+    Ok(())
+}
+
+/// Extract one bundled binary to its `.new` path, checking size and SHA-256 on the way.
+fn extract_verified(
+    archive: &mut zip::ZipArchive<fs::File>,
+    tool: &str,
+    bundled: &ToolBundleFile,
+    temp: &Path,
+) -> Result<(), String> {
+    let mut input = archive
+        .by_name(&entry_name(tool, &bundled.name))
+        .map_err(|e| format!("Failed to open {}: {}", bundled.name, e))?;
+    let mut output = fs::File::create(temp)
+        .map_err(|e| format!("Failed to create {}: {}", temp.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = input
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to extract {}: {}", bundled.name, e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        output
+            .write_all(&buffer[..read])
+            .map_err(|e| format!("Failed to extract {}: {}", bundled.name, e))?;
+        size += read as u64;
+        if size > bundled.size {
+            break;
+        }
+    }
+    output
+        .flush()
+        .map_err(|e| format!("Failed to flush {}: {}", bundled.name, e))?;
+
+    if size != bundled.size {
+        return Err(format!(
+            "{} size mismatch: manifest says {} bytes, bundle has {}",
+            bundled.name, bundled.size, size
+        ));
+    }
+    let actual = format!("{:x}", hasher.finalize());
+    if !actual.eq_ignore_ascii_case(bundled.sha256.trim()) {
+        return Err(format!(
+            "SHA-256 mismatch for {}: expected {}, got {}",
+            bundled.name, bundled.sha256, actual
+        ));
+    }
+
+    make_executable(temp)
+}
+
+fn import_bundle(
+    app_handle: &tauri::AppHandle,
+    bundle_path: &str,
+    tools: Option<Vec<String>>,
+) -> Result<ToolBundleResult, String> {
+    let file =
+        fs::File::open(bundle_path.trim()).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    let manifest = read_manifest(&mut archive)?;
+    check_entries(&archive, &manifest)?;
+
+    let selected: Vec<ToolBundleEntry> = match &tools {
+        Some(requested) => {
+            for tool in requested {
+                if !manifest.tools.iter().any(|entry| &entry.id == tool) {
+                    return Err(format!("{} is not in this bundle", tool));
+                }
+            }
+            manifest
+                .tools
+                .into_iter()
+                .filter(|entry| requested.contains(&entry.id))
+                .collect()
+        }
+        None => manifest.tools,
+    };
+
+    let paths = crate::app_paths::ensure_app_dirs(app_handle)?;
+    let bin_dir = PathBuf::from(&paths.bin_dir);
+    // Held until the manifest is updated, so an install of the same tool cannot interleave.
+    let _files = selected
+        .iter()
+        .map(|entry| try_lock_tool_files(app_handle, &bin_dir, tool_spec(&entry.id)?))
+        .collect::<Result<Vec<_>, String>>()?;
+    check_sizes(
+        &mut archive,
+        Path::new(bundle_path.trim()),
+        &bin_dir,
+        &selected,
+    )?;
+
+    // Verify every file before activating any, so a bad bundle changes nothing.
+    let mut staged: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for entry in &selected {
+        emit_progress(app_handle, &entry.id, 50.0, "Verifying offline bundle...");
+        for bundled in entry.bundled_files() {
+            let dest = bin_dir.join(&bundled.name);
+            let temp = temp_path_for(&dest)?;
+            let extracted = extract_verified(&mut archive, &entry.id, bundled, &temp);
+            staged.push((dest, temp));
+            if let Err(e) = extracted {
+                for (_, temp) in &staged {
+                    let _ = fs::remove_file(temp);
+                }
+                return Err(format!("{}: {}", entry.id, e));
+            }
+        }
+    }
+
+    let temps: Vec<PathBuf> = staged.iter().map(|(_, temp)| temp.clone()).collect();
+    let replacements = staged
+        .into_iter()
+        .map(|(dest, incoming)| Replacement { dest, incoming })
+        .collect();
+    if let Err(e) = replace_all(app_handle, "offline bundle", replacements, true) {
+        for temp in &temps {
+            let _ = fs::remove_file(temp);
+        }
+        return Err(e);
+    }
+    for entry in &selected {
+        let sha256 = entry
+            .files
+            .first()
+            .map(|file| file.sha256.as_str())
+            .unwrap_or("");
+        // Recording the libraries lets `finish_install` move the ones a previous (shared)
+        // install left behind into its backup generation, e.g. when the bundle is static.
+        let libraries = entry
+            .libraries
+            .iter()
+            .map(|file| file.name.clone())
+            .collect();
+        let mut installed =
+            ToolManifestEntry::new(&entry.id, Some(&bin_dir), InstallMethod::Bundle)
+                .with_source(bundle_path.trim(), sha256)
+                .with_libraries(libraries);
+        installed.version = installed.version.or_else(|| entry.version.clone());
+        finish_install(app_handle, &bin_dir, installed);
+        emit_progress(
+            app_handle,
+            &entry.id,
+            100.0,
+            "Installed from offline bundle",
+        );
+    }
+
+    Ok(ToolBundleResult {
+        path: bin_dir.to_string_lossy().to_string(),
+        tools: selected,
+    })
+}
+
+/// Pack the app-managed tools (all installed ones, or exactly `tools`) into a bundle zip.
+#[tauri::command]
+pub async fn export_tool_bundle(
+    app_handle: tauri::AppHandle,
+    output_path: String,
+    tools: Option<Vec<String>>,
+) -> Result<ToolBundleResult, String> {
+    tauri::async_runtime::spawn_blocking(move || export_bundle(&app_handle, &output_path, tools))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Install tools from a bundle made by `export_tool_bundle` into the app bin dir.
+/// Replaced binaries keep a `.old` backup, so `rollback_tool` works as after an online install.
+#[tauri::command]
+pub async fn import_tool_bundle(
+    app_handle: tauri::AppHandle,
+    bundle_path: String,
+    tools: Option<Vec<String>>,
+) -> Result<ToolBundleResult, String> {
+    tauri::async_runtime::spawn_blocking(move || import_bundle(&app_handle, &bundle_path, tools))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,252 @@
+//! Integrity scan of the app-managed binaries against the sizes and hashes recorded in the
+//! tool manifest, so a quarantined or truncated binary is caught before a job fails on it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::download::sha256_of_path;
+use crate::tool_locks::try_lock_tool_files;
+use crate::tool_manifest::{load_tool_manifest, ManifestFile};
+use crate::tool_specs::tool_spec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BinaryStatus {
+    Ok,
+    Missing,
+    Modified,
+    ZeroByte,
+}
+
+/// What to do about a damaged tool: swap its `.old` backup back in, or download it again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IntegrityRepair {
+    Restore,
+    Reinstall,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinaryIntegrity {
+    pub tool: String,
+    pub name: String,
+    pub path: String,
+    pub status: BinaryStatus,
+    pub expected_size: u64,
+    pub actual_size: Option<u64>,
+    pub expected_sha256: String,
+    /// Only computed when the size matches.
+    pub actual_sha256: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolRepairResult {
+    pub tool: String,
+    pub action: IntegrityRepair,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub bin_dir: String,
+    /// State after any repairs.
+    pub binaries: Vec<BinaryIntegrity>,
+    pub repairs: Vec<ToolRepairResult>,
+    /// Interrupted updates found and undone at startup.
+    pub recovered: Vec<String>,
+    pub all_ok: bool,
+}
+
+/// Last completed scan, so the UI can pick up the startup result whenever it is ready.
+#[derive(Default)]
+pub struct ToolIntegrityState {
+    last_report: Mutex<Option<IntegrityReport>>,
+}
+
+fn check_binary(tool: &str, path: &Path, expected: &ManifestFile) -> BinaryIntegrity {
+    let actual_size = fs::metadata(path).ok().map(|metadata| metadata.len());
+    let mut actual_sha256 = None;
+    let status = match actual_size {
+        None => BinaryStatus::Missing,
+        Some(0) => BinaryStatus::ZeroByte,
+        Some(size) if size != expected.size => BinaryStatus::Modified,
+        Some(_) => {
+            actual_sha256 = sha256_of_path(path).ok();
+            match &actual_sha256 {
+                Some(hash) if hash.eq_ignore_ascii_case(&expected.sha256) => BinaryStatus::Ok,
+                _ => BinaryStatus::Modified,
+            }
+        }
+    };
+    BinaryIntegrity {
+        tool: tool.to_string(),
+        name: expected.name.clone(),
+        path: path.to_string_lossy().to_string(),
+        status,
+        expected_size: expected.size,
+        actual_size,
+        expected_sha256: expected.sha256.clone(),
+        actual_sha256,
+    }
+}
+
+fn managed_bin_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let paths = crate::app_paths::resolve_paths(app_handle)?;
+    Ok(PathBuf::from(paths.bin_dir))
+}
+
+/// Check every binary the manifest records for the app bin dir.
+fn scan_managed_binaries(app_handle: &tauri::AppHandle) -> Result<Vec<BinaryIntegrity>, String> {
+    let bin_dir = managed_bin_dir(app_handle)?;
+    let manifest = load_tool_manifest(app_handle)?;
+    Ok(manifest
+        .tools
+        .iter()
+        .filter(|entry| entry.install_dir.as_deref().map(Path::new) == Some(bin_dir.as_path()))
+        .flat_map(|entry| {
+            entry
+                .files
+                .iter()
+                .map(|file| check_binary(&entry.tool, &bin_dir.join(&file.name), file))
+        })
+        .collect())
+}
+
+async fn scan_in_background(app_handle: &tauri::AppHandle) -> Result<Vec<BinaryIntegrity>, String> {
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || scan_managed_binaries(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+async fn repair_tool(
+    app_handle: &tauri::AppHandle,
+    tool: &str,
+    action: IntegrityRepair,
+    damaged: &[&BinaryIntegrity],
+) -> Result<String, String> {
+    match action {
+        // `rollback_tool` takes the tool's file locks itself.
+        IntegrityRepair::Restore => {
+            crate::tools::rollback_tool(app_handle.clone(), tool.to_string(), None, None)
+        }
+        IntegrityRepair::Reinstall => {
+            let bin_dir = managed_bin_dir(app_handle)?;
+            // Drop the damaged files first so the reinstall does not keep them as a backup
+            // generation. The locks keep this from racing an install of the same tool, and
+            // are released before the reinstall takes them again.
+            {
+                let _files = try_lock_tool_files(app_handle, &bin_dir, tool_spec(tool)?)?;
+                for binary in damaged {
+                    if binary.status != BinaryStatus::Missing {
+                        fs::remove_file(&binary.path)
+                            .map_err(|e| format!("Failed to remove {}: {}", binary.path, e))?;
+                    }
+                }
+            }
+            let channel = load_tool_manifest(app_handle)?
+                .tools
+                .into_iter()
+                .find(|entry| {
+                    entry.tool == tool
+                        && entry.install_dir.as_deref().map(Path::new) == Some(bin_dir.as_path())
+                })
+                .and_then(|entry| entry.channel);
+            crate::tools::reinstall_managed_tool(app_handle, tool, channel.as_deref()).await
+        }
+    }
+}
+
+async fn run_integrity_scan(
+    app_handle: &tauri::AppHandle,
+    repair: Option<IntegrityRepair>,
+    recovered: Vec<String>,
+) -> Result<IntegrityReport, String> {
+    let mut binaries = scan_in_background(app_handle).await?;
+
+    let mut repairs = Vec::new();
+    if let Some(action) = repair {
+        let mut damaged_tools: Vec<String> = Vec::new();
+        for binary in &binaries {
+            if binary.status != BinaryStatus::Ok && !damaged_tools.contains(&binary.tool) {
+                damaged_tools.push(binary.tool.clone());
+            }
+        }
+        for tool in damaged_tools {
+            let damaged: Vec<&BinaryIntegrity> = binaries
+                .iter()
+                .filter(|binary| binary.tool == tool && binary.status != BinaryStatus::Ok)
+                .collect();
+            let (success, message) = match repair_tool(app_handle, &tool, action, &damaged).await {
+                Ok(message) => (true, message),
+                Err(e) => (false, e),
+            };
+            repairs.push(ToolRepairResult {
+                tool,
+                action,
+                success,
+                message,
+            });
+        }
+        if !repairs.is_empty() {
+            binaries = scan_in_background(app_handle).await?;
+        }
+    }
+
+    let report = IntegrityReport {
+        bin_dir: managed_bin_dir(app_handle)?.to_string_lossy().to_string(),
+        all_ok: binaries
+            .iter()
+            .all(|binary| binary.status == BinaryStatus::Ok),
+        binaries,
+        repairs,
+        recovered,
+    };
+    if let Ok(mut last) = app_handle.state::<ToolIntegrityState>().last_report.lock() {
+        *last = Some(report.clone());
+    }
+    if !report.all_ok || !report.repairs.is_empty() || !report.recovered.is_empty() {
+        let _ = app_handle.emit("tool-integrity", report.clone());
+    }
+    Ok(report)
+}
+
+/// Scan the managed binaries off the startup path; problems are announced via `tool-integrity`.
+/// `recovered` lists interrupted updates already repaired during setup.
+pub fn spawn_startup_scan(app_handle: tauri::AppHandle, recovered: Vec<String>) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run_integrity_scan(&app_handle, None, recovered).await {
+            eprintln!("[tools] Warning: tool integrity scan failed: {}", e);
+        }
+    });
+}
+
+/// Check managed binaries against the tool manifest; `repair` restores or reinstalls damaged tools.
+#[tauri::command]
+pub async fn scan_tool_integrity(
+    app_handle: tauri::AppHandle,
+    repair: Option<IntegrityRepair>,
+) -> Result<IntegrityReport, String> {
+    run_integrity_scan(&app_handle, repair, Vec::new()).await
+}
+
+/// Result of the most recent scan (the startup one until the UI runs another).
+#[tauri::command]
+pub fn get_tool_integrity_report(
+    app_handle: tauri::AppHandle,
+) -> Result<Option<IntegrityReport>, String> {
+    let state = app_handle.state::<ToolIntegrityState>();
+    let last = state
+        .last_report
+        .lock()
+        .map_err(|_| "Tool integrity state poisoned")?;
+    Ok(last.clone())
+}
@@ -0,0 +1,89 @@
+//! Per-file locks on installed tool binaries, so two installs writing the same file (a batch
+//! install and an in-place update of the same tool, say) take turns instead of racing.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+use crate::tool_manifest::recorded_entry;
+use crate::tool_specs::ToolSpec;
+
+#[derive(Default)]
+pub struct ToolFileLocks {
+    files: Mutex<HashMap<PathBuf, Arc<AsyncMutex<()>>>>,
+}
+
+/// Held while a tool's files are being written; releases them when dropped.
+pub struct ToolFilesGuard {
+    _guards: Vec<OwnedMutexGuard<()>>,
+}
+
+/// Locks for every binary of `spec` in `dir` and the shared libraries its recorded install
+/// placed there, in a stable order so that two callers locking overlapping sets cannot
+/// deadlock.
+fn file_locks(
+    app_handle: &AppHandle,
+    dir: &Path,
+    spec: &dyn ToolSpec,
+) -> Result<Vec<Arc<AsyncMutex<()>>>, String> {
+    let libraries = recorded_entry(app_handle, spec.id(), dir)
+        .map(|entry| entry.libraries)
+        .unwrap_or_default();
+    // `dest_dir` arguments come from the UI in whatever form; key on the resolved directory.
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let mut paths: Vec<PathBuf> = spec
+        .binaries()
+        .iter()
+        .copied()
+        .chain(libraries.iter().map(String::as_str))
+        .map(|name| dir.join(name))
+        .collect();
+    paths.sort();
+    paths.dedup();
+
+    let registry = app_handle.state::<ToolFileLocks>();
+    let mut files = registry
+        .files
+        .lock()
+        .map_err(|_| "Tool file lock registry poisoned".to_string())?;
+    Ok(paths
+        .into_iter()
+        .map(|path| files.entry(path).or_default().clone())
+        .collect())
+}
+
+/// Wait until no other install is writing the files of `spec` in `dir`.
+pub async fn lock_tool_files(
+    app_handle: &AppHandle,
+    dir: &Path,
+    spec: &dyn ToolSpec,
+) -> Result<ToolFilesGuard, String> {
+    let mut guards = Vec::new();
+    for lock in file_locks(app_handle, dir, spec)? {
+        guards.push(lock.lock_owned().await);
+    }
+    Ok(ToolFilesGuard { _guards: guards })
+}
+
+/// Like `lock_tool_files`, but fails instead of waiting (for synchronous commands).
+pub fn try_lock_tool_files(
+    app_handle: &AppHandle,
+    dir: &Path,
+    spec: &dyn ToolSpec,
+) -> Result<ToolFilesGuard, String> {
+    let mut guards = Vec::new();
+    for lock in file_locks(app_handle, dir, spec)? {
+        let guard = lock.try_lock_owned().map_err(|_| {
+            format!(
+                "{} is already being installed in {}",
+                spec.id(),
+                dir.display()
+            )
+        })?;
+        guards.push(guard);
+    }
+    Ok(ToolFilesGuard { _guards: guards })
+}
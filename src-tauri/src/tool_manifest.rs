@@ -0,0 +1,314 @@
+//! `tools-manifest.json` in the state dir: what HalalDL installed for each tool, how, from
+//! where, and the size and SHA-256 of every file it put down.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::download::sha256_of_path;
+use crate::fs_utils::temp_path_for;
+use crate::tool_specs::tool_spec;
+
+pub const TOOL_MANIFEST_FILE: &str = "tools-manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallMethod {
+    Download,
+    Manual,
+    Bundle,
+    Pip,
+    /// pipx, uv tool, conda or Homebrew upgraded it.
+    PackageManager,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestFile {
+    pub name: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolManifestEntry {
+    pub tool: String,
+    /// Directory the tool lives in; `None` when pip installed it somewhere unknown.
+    pub install_dir: Option<String>,
+    pub version: Option<String>,
+    pub channel: Option<String>,
+    /// Build variant (`essentials`, `full`, `shared`), kept by later updates.
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// Download URL, or the file the tool was copied from for manual and bundle installs.
+    pub source_url: Option<String>,
+    /// SHA-256 of what was downloaded or copied (the archive for zip-packaged tools).
+    pub sha256: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub installed_at: u64,
+    pub method: InstallMethod,
+    pub files: Vec<ManifestFile>,
+    /// Shared libraries installed next to the binaries; also listed in `files`.
+    #[serde(default)]
+    pub libraries: Vec<String>,
+    /// The install this one replaced, which `rollback_tool` brings back from `.old`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<Box<ToolManifestEntry>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolManifest {
+    pub version: u32,
+    pub tools: Vec<ToolManifestEntry>,
+}
+
+impl Default for ToolManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            tools: Vec::new(),
+        }
+    }
+}
+
+/// Serializes read-modify-write cycles on the manifest file.
+#[derive(Default)]
+pub struct ToolManifestStore {
+    lock: Mutex<()>,
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn dir_key(dir: &Path) -> String {
+    dir.to_string_lossy().to_string()
+}
+
+fn describe_file(dir: &Path, name: &str) -> Option<ManifestFile> {
+    let path = dir.join(name);
+    let size = fs::metadata(&path).ok()?.len();
+    let sha256 = sha256_of_path(&path).ok()?;
+    Some(ManifestFile {
+        name: name.to_string(),
+        sha256,
+        size,
+    })
+}
+
+impl ToolManifestEntry {
+    /// Entry for `tool` in `install_dir`, with its files hashed and its version probed.
+    pub fn new(tool: &str, install_dir: Option<&Path>, method: InstallMethod) -> Self {
+        let mut entry = Self {
+            tool: tool.to_string(),
+            install_dir: install_dir.map(dir_key),
+            version: None,
+            channel: None,
+            variant: None,
+            source_url: None,
+            sha256: None,
+            installed_at: now_ms(),
+            method,
+            files: Vec::new(),
+            libraries: Vec::new(),
+            previous: None,
+        };
+        entry.refresh_files();
+        entry
+    }
+
+    pub fn with_source(mut self, source_url: &str, sha256: &str) -> Self {
+        self.source_url = Some(source_url.to_string());
+        self.sha256 = Some(sha256.to_string());
+        self
+    }
+
+    pub fn with_channel(mut self, channel: Option<&str>) -> Self {
+        self.channel = channel.map(str::to_string);
+        self
+    }
+
+    pub fn with_variant(mut self, variant: Option<&str>) -> Self {
+        self.variant = variant.map(str::to_string);
+        self
+    }
+
+    /// Record the shared libraries an install put next to the binaries, with their hashes.
+    pub fn with_libraries(mut self, libraries: Vec<String>) -> Self {
+        if let Some(dir) = self.install_dir.clone() {
+            let dir = Path::new(&dir);
+            self.files
+                .extend(libraries.iter().filter_map(|name| describe_file(dir, name)));
+        }
+        self.libraries = libraries;
+        self
+    }
+
+    /// Re-hash the tool's binaries and libraries in `install_dir` and re-probe its version.
+    fn refresh_files(&mut self) {
+        let (Some(dir), Ok(spec)) = (self.install_dir.as_deref(), tool_spec(&self.tool)) else {
+            return;
+        };
+        let dir = Path::new(dir);
+        self.files = spec
+            .binaries()
+            .iter()
+            .copied()
+            .chain(self.libraries.iter().map(String::as_str))
+            .filter_map(|name| describe_file(dir, name))
+            .collect();
+        self.version = spec.probe_version(&dir.join(spec.primary_binary()));
+    }
+
+    fn is_for(&self, tool: &str, install_dir: Option<&str>) -> bool {
+        self.tool == tool && self.install_dir.as_deref() == install_dir
+    }
+}
+
+pub fn tool_manifest_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let paths = crate::app_paths::resolve_paths(app_handle)?;
+    Ok(PathBuf::from(paths.state_dir).join(TOOL_MANIFEST_FILE))
+}
+
+fn read_manifest(path: &Path) -> Result<ToolManifest, String> {
+    if !path.exists() {
+        return Ok(ToolManifest::default());
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+fn write_manifest(path: &Path, manifest: &ToolManifest) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    let temp = temp_path_for(path)?;
+    fs::write(&temp, json).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    fs::rename(&temp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+pub fn load_tool_manifest(app_handle: &tauri::AppHandle) -> Result<ToolManifest, String> {
+    let store = app_handle.state::<ToolManifestStore>();
+    let _guard = store
+        .lock
+        .lock()
+        .map_err(|_| "Tool manifest lock poisoned")?;
+    read_manifest(&tool_manifest_path(app_handle)?)
+}
+
+/// Apply `change` to the manifest on disk. A corrupt manifest is started over rather than
+/// blocking installs; failures are logged, since the install itself already succeeded.
+fn update_manifest(app_handle: &tauri::AppHandle, change: impl FnOnce(&mut ToolManifest)) {
+    let result = (|| {
+        let store = app_handle.state::<ToolManifestStore>();
+        let _guard = store
+            .lock
+            .lock()
+            .map_err(|_| "Tool manifest lock poisoned")?;
+        let path = tool_manifest_path(app_handle)?;
+        let mut manifest = read_manifest(&path).unwrap_or_else(|e| {
+            eprintln!("[tools] Warning: {}; starting a new tool manifest", e);
+            ToolManifest::default()
+        });
+        change(&mut manifest);
+        manifest.version = MANIFEST_VERSION;
+        write_manifest(&path, &manifest)
+    })();
+    if let Err(e) = result {
+        eprintln!("[tools] Warning: failed to update tool manifest: {}", e);
+    }
+}
+
+/// Current entry for `tool` in `install_dir`, if one was recorded.
+pub fn recorded_entry(
+    app_handle: &tauri::AppHandle,
+    tool: &str,
+    install_dir: &Path,
+) -> Option<ToolManifestEntry> {
+    let dir = dir_key(install_dir);
+    load_tool_manifest(app_handle)
+        .ok()?
+        .tools
+        .into_iter()
+        .find(|entry| entry.is_for(tool, Some(&dir)))
+}
+
+/// Record a completed install; the entry it replaces becomes its `previous` and is returned.
+pub fn record_install(
+    app_handle: &tauri::AppHandle,
+    mut entry: ToolManifestEntry,
+) -> Option<ToolManifestEntry> {
+    let mut replaced_entry = None;
+    update_manifest(app_handle, |manifest| {
+        let tool = entry.tool.clone();
+        let dir = entry.install_dir.clone();
+        if let Some(index) = manifest
+            .tools
+            .iter()
+            .position(|existing| existing.is_for(&tool, dir.as_deref()))
+        {
+            let mut replaced = manifest.tools.remove(index);
+            replaced.previous = None;
+            replaced_entry = Some(replaced.clone());
+            entry.previous = Some(Box::new(replaced));
+        }
+        manifest.tools.push(entry);
+    });
+    replaced_entry
+}
+
+/// Record that `tool` in `install_dir` was rolled back, to `restored` when the backup carried
+/// its own entry and otherwise to the `previous` of the current one.
+pub fn record_rollback(
+    app_handle: &tauri::AppHandle,
+    tool: &str,
+    install_dir: &Path,
+    restored: Option<ToolManifestEntry>,
+) {
+    let dir = dir_key(install_dir);
+    update_manifest(app_handle, |manifest| {
+        let existing = manifest
+            .tools
+            .iter()
+            .position(|entry| entry.is_for(tool, Some(&dir)))
+            .map(|index| manifest.tools.remove(index));
+        let previous = existing
+            .and_then(|entry| entry.previous)
+            .map(|previous| *previous);
+        let mut restored = match restored.or(previous) {
+            Some(entry) => entry,
+            // Nothing recorded for the backup; describe the restored files as found.
+            None => ToolManifestEntry::new(tool, Some(install_dir), InstallMethod::Manual),
+        };
+        restored.refresh_files();
+        manifest.tools.push(restored);
+    });
+}
+
+/// Drop the entry for `tool` in `install_dir`, e.g. after its binaries were deleted.
+pub fn forget_tool(app_handle: &tauri::AppHandle, tool: &str, install_dir: &Path) {
+    let dir = dir_key(install_dir);
+    update_manifest(app_handle, |manifest| {
+        manifest
+            .tools
+            .retain(|entry| !entry.is_for(tool, Some(&dir)));
+    });
+}
+
+/// Installed tools as recorded by every install, update, stage, import and rollback.
+#[tauri::command]
+pub fn get_tool_manifest(app_handle: tauri::AppHandle) -> Result<ToolManifest, String> {
+    load_tool_manifest(&app_handle)
+}
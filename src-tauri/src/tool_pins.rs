@@ -0,0 +1,136 @@
+//! `tool-pins.json` in the state dir: release tags the user pinned tools to. Installs that
+//! name no version use the pin, and the latest-version checks report it, so automatic
+//! updates stay on a pinned release until the pin is cleared.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::fs_utils::temp_path_for;
+use crate::tool_manifest::now_ms;
+use crate::tool_specs::tool_spec;
+
+pub const TOOL_PINS_FILE: &str = "tool-pins.json";
+/// Requested version that installs the newest release and clears any pin.
+pub const LATEST_VERSION: &str = "latest";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolPin {
+    /// Release tag as published upstream, e.g. `2025.01.15` or `release-1.37.0`.
+    pub version: String,
+    /// Milliseconds since the Unix epoch.
+    pub pinned_at: u64,
+}
+
+/// Serializes read-modify-write cycles on the pins file.
+#[derive(Default)]
+pub struct ToolPinStore {
+    lock: Mutex<()>,
+}
+
+/// Reject tags that could not be a release name, since they end up in download URLs.
+pub fn check_version_tag(version: &str) -> Result<&str, String> {
+    let version = version.trim();
+    let valid = !version.is_empty()
+        && version.len() <= 64
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'));
+    if valid {
+        Ok(version)
+    } else {
+        Err(format!("Invalid tool version: {}", version))
+    }
+}
+
+fn tool_pins_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let paths = crate::app_paths::resolve_paths(app_handle)?;
+    Ok(PathBuf::from(paths.state_dir).join(TOOL_PINS_FILE))
+}
+
+fn read_pins(path: &Path) -> Result<BTreeMap<String, ToolPin>, String> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+fn write_pins(path: &Path, pins: &BTreeMap<String, ToolPin>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(pins).map_err(|e| e.to_string())?;
+    let temp = temp_path_for(path)?;
+    fs::write(&temp, json).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    fs::rename(&temp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+pub fn load_tool_pins(app_handle: &tauri::AppHandle) -> Result<BTreeMap<String, ToolPin>, String> {
+    let store = app_handle.state::<ToolPinStore>();
+    let _guard = store.lock.lock().map_err(|_| "Tool pin lock poisoned")?;
+    read_pins(&tool_pins_path(app_handle)?)
+}
+
+/// Release `tool` is pinned to, if any. An unreadable pins file counts as no pins.
+pub fn pinned_version(app_handle: &tauri::AppHandle, tool: &str) -> Option<String> {
+    load_tool_pins(app_handle)
+        .ok()?
+        .remove(tool)
+        .map(|pin| pin.version)
+}
+
+/// Pin `tool` to `version`, or clear its pin when `version` is `None`.
+pub fn set_pin(
+    app_handle: &tauri::AppHandle,
+    tool: &str,
+    version: Option<&str>,
+) -> Result<(), String> {
+    let version = version.map(check_version_tag).transpose()?;
+    let store = app_handle.state::<ToolPinStore>();
+    let _guard = store.lock.lock().map_err(|_| "Tool pin lock poisoned")?;
+    let path = tool_pins_path(app_handle)?;
+    let mut pins = read_pins(&path).unwrap_or_else(|e| {
+        eprintln!("[tools] Warning: {}; starting a new pins file", e);
+        BTreeMap::new()
+    });
+    match version {
+        Some(version) => {
+            pins.insert(
+                tool.to_string(),
+                ToolPin {
+                    version: version.to_string(),
+                    pinned_at: now_ms(),
+                },
+            );
+        }
+        None => {
+            pins.remove(tool);
+        }
+    }
+    write_pins(&path, &pins)
+}
+
+/// Pinned release per tool id.
+#[tauri::command]
+pub fn get_tool_pins(app_handle: tauri::AppHandle) -> Result<BTreeMap<String, ToolPin>, String> {
+    load_tool_pins(&app_handle)
+}
+
+/// Pin `tool` to release `version` without installing it; `None` (or `latest`) clears the pin.
+#[tauri::command]
+pub fn set_tool_pin(
+    app_handle: tauri::AppHandle,
+    tool: String,
+    version: Option<String>,
+) -> Result<(), String> {
+    tool_spec(&tool)?;
+    let version = version.filter(|version| version.trim() != LATEST_VERSION);
+    set_pin(&app_handle, &tool, version.as_deref())
+}
@@ -0,0 +1,451 @@
+//! Dry run of `update_tool_at_path`: checks that the target directory, binaries and shared
+//! libraries can be written, that the volume has room, that no running process holds them
+//! and that no package manager owns them, without modifying anything the tool uses. The
+//! update itself refuses to start when any check fails, and checks again once it holds the
+//! tool's file locks.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::archive_limits::ensure_disk_space;
+use crate::asset_resolver::glob_matches;
+use crate::fs_utils::{available_space, temp_path_for};
+use crate::tool_manifest::recorded_entry;
+use crate::tool_sources::{load_tool_sources, normalize_variant};
+use crate::tool_specs::{tool_spec, ToolSpec};
+use crate::ytdlp_install::{detect_ytdlp_install, YtDlpInstallMethod};
+
+/// Room assumed for a download when the tool is not installed in the directory yet.
+const MIN_UPDATE_SPACE: u64 = 256 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PreflightCheckKind {
+    Writable,
+    DiskSpace,
+    RunningProcesses,
+    PackageManager,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightCheck {
+    pub kind: PreflightCheckKind,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedFile {
+    pub name: String,
+    pub path: String,
+    /// `false` when the update would create the file rather than replace it.
+    pub exists: bool,
+    pub size: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolUpdatePlan {
+    pub tool: String,
+    pub dest_dir: String,
+    /// Files the update would write: the binaries, then the shared libraries.
+    pub files: Vec<PlannedFile>,
+    /// Bytes the download and the new files are expected to need.
+    pub estimated_bytes: u64,
+    pub available_bytes: Option<u64>,
+    pub checks: Vec<PreflightCheck>,
+    /// Every check passed; `update_tool_at_path` would start.
+    pub ready: bool,
+}
+
+impl ToolUpdatePlan {
+    /// Details of the failed checks, or `None` when the update may proceed.
+    pub fn failure(&self) -> Option<String> {
+        let failed: Vec<&str> = self
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.detail.as_str())
+            .collect();
+        (!failed.is_empty()).then(|| failed.join("; "))
+    }
+}
+
+fn check(kind: PreflightCheckKind, result: Result<String, String>) -> PreflightCheck {
+    let (passed, detail) = match result {
+        Ok(detail) => (true, detail),
+        Err(detail) => (false, detail),
+    };
+    PreflightCheck {
+        kind,
+        passed,
+        detail,
+    }
+}
+
+/// Windows error for a file opened without sharing by another process.
+#[cfg(target_os = "windows")]
+const ERROR_SHARING_VIOLATION: i32 = 32;
+
+/// Whether another process has `path` open in a way that blocks replacing it.
+#[cfg(target_os = "windows")]
+fn is_locked(path: &Path) -> bool {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    fs::OpenOptions::new()
+        .write(true)
+        .share_mode(0)
+        .open(path)
+        .err()
+        .and_then(|e| e.raw_os_error())
+        == Some(ERROR_SHARING_VIOLATION)
+}
+
+fn check_writable(dest: &Path, tool: &str, files: &[PlannedFile]) -> Result<String, String> {
+    // New files are created next to the old ones and renamed over them.
+    let probe = temp_path_for(&dest.join(format!(".{}-preflight", tool)))?;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| format!("Cannot create files in {}: {}", dest.display(), e))?;
+    let _ = fs::remove_file(&probe);
+
+    for file in files.iter().filter(|file| file.exists) {
+        let path = Path::new(&file.path);
+        let metadata =
+            fs::metadata(path).map_err(|e| format!("Cannot read {}: {}", file.path, e))?;
+        if metadata.permissions().readonly() {
+            return Err(format!("{} is read-only", file.path));
+        }
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::fs::OpenOptionsExt;
+
+            if let Err(e) = fs::OpenOptions::new().write(true).share_mode(7).open(path) {
+                if e.raw_os_error() != Some(ERROR_SHARING_VIOLATION) {
+                    return Err(format!("Cannot write {}: {}", file.path, e));
+                }
+            }
+        }
+    }
+    Ok(format!("{} is writable", dest.display()))
+}
+
+fn check_disk_space(dest: &Path, needed: u64) -> Result<String, String> {
+    ensure_disk_space(dest, needed).map_err(|e| e.to_string())?;
+    Ok(match available_space(dest) {
+        Some(available) => format!(
+            "{} MiB free, about {} MiB needed",
+            available / (1024 * 1024),
+            needed / (1024 * 1024)
+        ),
+        None => "Free space could not be read; not checked".to_string(),
+    })
+}
+
+/// `(pid, executable)` of every running process started from one of `paths`. Script
+/// installs run under their interpreter and never match, which is fine: scripts can be
+/// replaced while they run.
+#[cfg(target_os = "linux")]
+fn processes_using(paths: &[PathBuf]) -> Vec<(u32, String)> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let exe = fs::read_link(entry.path().join("exe")).ok()?;
+            let exe = exe.to_string_lossy();
+            // A replaced but still running binary shows as `path (deleted)`.
+            let exe = exe.trim_end_matches(" (deleted)");
+            paths
+                .iter()
+                .any(|path| Path::new(exe) == path)
+                .then(|| (pid, exe.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn processes_using(paths: &[PathBuf]) -> Vec<(u32, String)> {
+    let Ok(output) = std::process::Command::new("ps")
+        .args(["-axo", "pid=,comm="])
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (pid, exe) = line.trim().split_once(' ')?;
+            let pid: u32 = pid.parse().ok()?;
+            let exe = exe.trim();
+            paths
+                .iter()
+                .any(|path| Path::new(exe) == path)
+                .then(|| (pid, exe.to_string()))
+        })
+        .collect()
+}
+
+/// Windows cannot list process paths without extra APIs, so a binary counts as held when
+/// it is locked, and the processes are the ones running under its file name.
+#[cfg(target_os = "windows")]
+fn processes_using(paths: &[PathBuf]) -> Vec<(u32, String)> {
+    use std::os::windows::process::CommandExt;
+
+    let mut found = Vec::new();
+    for path in paths.iter().filter(|path| is_locked(path)) {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let output = std::process::Command::new("tasklist")
+            .args(["/FO", "CSV", "/NH", "/FI"])
+            .arg(format!("IMAGENAME eq {}", name))
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .output();
+        let pids: Vec<u32> = output
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter_map(|line| line.split(',').nth(1)?.trim_matches('"').parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if pids.is_empty() {
+            // Locked by something tasklist does not attribute (an editor, antivirus, ...).
+            found.push((0, path.to_string_lossy().to_string()));
+        }
+        found.extend(
+            pids.into_iter()
+                .map(|pid| (pid, path.to_string_lossy().to_string())),
+        );
+    }
+    found
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn processes_using(_paths: &[PathBuf]) -> Vec<(u32, String)> {
+    Vec::new()
+}
+
+fn check_processes(binaries: &[PathBuf]) -> Result<String, String> {
+    let running = processes_using(binaries);
+    if running.is_empty() {
+        return Ok("No running process holds the binaries".to_string());
+    }
+    let described: Vec<String> = running
+        .iter()
+        .map(|(pid, exe)| match pid {
+            0 => format!("{} is locked by another program", exe),
+            pid => format!("{} (pid {})", exe, pid),
+        })
+        .collect();
+    Err(format!("Close these first: {}", described.join(", ")))
+}
+
+/// Package manager that installed `path`, judged by where the file really lives.
+fn package_manager_owner(spec: &dyn ToolSpec, path: &Path) -> Option<String> {
+    if spec.id() == "yt-dlp" {
+        let install = detect_ytdlp_install(path);
+        let owner = match install.method {
+            YtDlpInstallMethod::Pipx => Some("pipx"),
+            YtDlpInstallMethod::UvTool => Some("uv tool"),
+            YtDlpInstallMethod::Venv => Some("a Python virtualenv"),
+            YtDlpInstallMethod::Conda => Some("conda"),
+            YtDlpInstallMethod::Pip => Some("pip"),
+            _ => None,
+        };
+        if let Some(owner) = owner {
+            return Some(owner.to_string());
+        }
+    }
+
+    let real = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let lower = real
+        .to_string_lossy()
+        .to_ascii_lowercase()
+        .replace('\\', "/");
+    let owners = [
+        ("/chocolatey/", "Chocolatey"),
+        ("/scoop/", "Scoop"),
+        ("/winget/packages/", "winget"),
+        ("/windowsapps/", "the Microsoft Store"),
+        ("/cellar/", "Homebrew"),
+        ("/nix/store/", "Nix"),
+        ("/snap/", "snap"),
+    ];
+    if let Some((_, owner)) = owners.iter().find(|(dir, _)| lower.contains(dir)) {
+        return Some(owner.to_string());
+    }
+    let system_dirs = ["/usr/bin/", "/usr/lib/", "/usr/share/", "/bin/"];
+    (cfg!(not(target_os = "windows")) && system_dirs.iter().any(|dir| lower.starts_with(dir)))
+        .then(|| "the system package manager".to_string())
+}
+
+fn check_package_manager(
+    spec: &dyn ToolSpec,
+    dest: &Path,
+    files: &[PlannedFile],
+) -> Result<String, String> {
+    let owned: Vec<String> = files
+        .iter()
+        .map(|file| PathBuf::from(&file.path))
+        .chain(std::iter::once(dest.to_path_buf()))
+        .filter(|path| path.exists())
+        .filter_map(|path| {
+            package_manager_owner(spec, &path)
+                .map(|owner| format!("{} is managed by {}", path.display(), owner))
+        })
+        .collect();
+    if owned.is_empty() {
+        Ok("Not owned by a package manager".to_string())
+    } else {
+        Err(format!(
+            "{}; update {} with that package manager instead",
+            owned.join(", "),
+            spec.id()
+        ))
+    }
+}
+
+/// Shared libraries an update of `spec` in `dest` would replace or create: the ones
+/// recorded for the current install and the files in `dest` matching the library
+/// patterns of `variant` (without one, the recorded variant).
+fn planned_libraries(
+    app_handle: &tauri::AppHandle,
+    spec: &dyn ToolSpec,
+    dest: &Path,
+    variant: Option<&str>,
+) -> Vec<String> {
+    let recorded = recorded_entry(app_handle, spec.id(), dest);
+    let variant = variant.or(recorded.as_ref().and_then(|entry| entry.variant.as_deref()));
+    let patterns = load_tool_sources(app_handle)
+        .ok()
+        .and_then(|registry| {
+            let sources = registry.tool(spec.source_id()).ok()?;
+            let asset = sources.platform_asset(spec.id(), variant).ok()?;
+            Some(asset.libraries.clone())
+        })
+        .unwrap_or_default();
+    let mut libraries: Vec<String> = recorded.map(|entry| entry.libraries).unwrap_or_default();
+    if !patterns.is_empty() {
+        if let Ok(entries) = fs::read_dir(dest) {
+            libraries.extend(
+                entries
+                    .flatten()
+                    .filter(|entry| entry.path().is_file())
+                    .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+                    .filter(|name| patterns.iter().any(|pattern| glob_matches(pattern, name))),
+            );
+        }
+    }
+    libraries.retain(|name| !spec.owns_binary(name));
+    libraries.sort();
+    libraries.dedup();
+    libraries
+}
+
+/// Run every check for updating `spec` in `dest` to `variant` without modifying anything
+/// there.
+pub fn plan_tool_update(
+    app_handle: &tauri::AppHandle,
+    spec: &dyn ToolSpec,
+    dest: &Path,
+    variant: Option<&str>,
+) -> ToolUpdatePlan {
+    let libraries = planned_libraries(app_handle, spec, dest, variant);
+    let files: Vec<PlannedFile> = spec
+        .binaries()
+        .iter()
+        .map(|name| name.to_string())
+        .chain(libraries)
+        .map(|name| {
+            let path = dest.join(&name);
+            let size = fs::metadata(&path).ok().map(|metadata| metadata.len());
+            PlannedFile {
+                name,
+                path: path.to_string_lossy().to_string(),
+                exists: size.is_some(),
+                size,
+            }
+        })
+        .collect();
+    let existing: u64 = files.iter().filter_map(|file| file.size).sum();
+    // The download lands next to the files before replacing them.
+    let estimated_bytes = existing.saturating_mul(2).max(MIN_UPDATE_SPACE);
+    let binaries: Vec<PathBuf> = files
+        .iter()
+        .filter(|file| file.exists)
+        .map(|file| {
+            let path = PathBuf::from(&file.path);
+            path.canonicalize().unwrap_or(path)
+        })
+        .collect();
+
+    let mut checks = Vec::new();
+    if dest.is_dir() {
+        checks.push(check(
+            PreflightCheckKind::Writable,
+            check_writable(dest, spec.id(), &files),
+        ));
+        checks.push(check(
+            PreflightCheckKind::DiskSpace,
+            check_disk_space(dest, estimated_bytes),
+        ));
+    } else {
+        checks.push(check(
+            PreflightCheckKind::Writable,
+            Err(format!("Directory does not exist: {}", dest.display())),
+        ));
+    }
+    checks.push(check(
+        PreflightCheckKind::RunningProcesses,
+        check_processes(&binaries),
+    ));
+    // The app's own bin dir may sit inside a package-managed install of HalalDL itself.
+    let app_bin_dir = crate::app_paths::resolve_paths(app_handle)
+        .ok()
+        .and_then(|paths| PathBuf::from(paths.bin_dir).canonicalize().ok());
+    if app_bin_dir.is_none() || dest.canonicalize().ok() != app_bin_dir {
+        checks.push(check(
+            PreflightCheckKind::PackageManager,
+            check_package_manager(spec, dest, &files),
+        ));
+    }
+
+    ToolUpdatePlan {
+        tool: spec.id().to_string(),
+        dest_dir: dest.to_string_lossy().to_string(),
+        files,
+        estimated_bytes,
+        available_bytes: available_space(dest),
+        ready: checks.iter().all(|check| check.passed),
+        checks,
+    }
+}
+
+/// What `update_tool_at_path` would write for `tool` in `dest_dir`, and whether it may start.
+/// `variant` is the FFmpeg build the update would install; without one the recorded one.
+#[tauri::command]
+pub async fn preflight_tool_update(
+    app_handle: tauri::AppHandle,
+    tool: String,
+    dest_dir: String,
+    variant: Option<String>,
+) -> Result<ToolUpdatePlan, String> {
+    let spec = tool_spec(&tool)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let variant = variant.as_deref().and_then(normalize_variant);
+        plan_tool_update(&app_handle, spec, Path::new(&dest_dir), variant)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,450 @@
+//! Versioned registry of where each managed tool is downloaded from. The bundled
+//! `tool-sources.json` ships with the app; a `tool-sources.json` in the state dir overrides
+//! it per tool, so mirrors and asset names can change without a new release.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_paths::resolve_paths;
+use crate::asset_resolver::{score_asset, HostPlatform};
+use crate::checksums::ExpectedChecksum;
+use crate::download::resolve_github_release_asset;
+use crate::tool_specs::ToolSpec;
+
+/// Registry schema version this build understands.
+pub const SUPPORTED_REGISTRY_VERSION: u32 = 1;
+const BUNDLED_REGISTRY: &str = include_str!("../tool-sources.json");
+const OVERRIDE_FILE_NAME: &str = "tool-sources.json";
+pub const DEFAULT_CHANNEL: &str = "stable";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolSourceRegistry {
+    pub version: u32,
+    pub tools: BTreeMap<String, ToolSourceSpec>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolSourceSpec {
+    /// Upstream repository (`owner/name`) the tool is released from.
+    #[serde(default)]
+    pub github: Option<String>,
+    /// Sources per update channel, primary first; unknown channels use `stable`.
+    #[serde(default)]
+    pub channels: BTreeMap<String, Vec<SourceSpec>>,
+    pub platforms: Vec<PlatformAsset>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformAsset {
+    /// `std::env::consts::OS` value, e.g. `windows`.
+    pub os: String,
+    /// `std::env::consts::ARCH` value, e.g. `x86_64`.
+    pub arch: String,
+    /// Asset file name; `*` and `?` wildcards are matched against GitHub release assets.
+    pub asset: String,
+    /// Lower-priority alternatives to `asset` for GitHub releases that rename their builds.
+    #[serde(default)]
+    pub fallback_assets: Vec<String>,
+    /// Release assets to skip even though they match `asset`, e.g. shared builds that
+    /// share a prefix with the static ones.
+    #[serde(default)]
+    pub exclude_assets: Vec<String>,
+    /// Files extracted from the archive; empty when the asset is the binary itself.
+    #[serde(default)]
+    pub members: Vec<String>,
+    /// Installed file name for non-archive assets; defaults to `asset`.
+    #[serde(default)]
+    pub file_name: Option<String>,
+    /// Build variant this entry installs; the first entry for a platform is the default.
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// Shared-library patterns (e.g. `*.dll`) extracted next to the members.
+    #[serde(default)]
+    pub libraries: Vec<String>,
+    /// Platform-specific sources, used instead of the tool's `channels` when present.
+    #[serde(default)]
+    pub channels: BTreeMap<String, Vec<SourceSpec>>,
+}
+
+impl PlatformAsset {
+    pub fn is_archive(&self) -> bool {
+        !self.members.is_empty()
+    }
+
+    pub fn installed_name(&self) -> &str {
+        self.file_name.as_deref().unwrap_or(&self.asset)
+    }
+
+    /// Member names followed by library patterns, as passed to the archive extractors.
+    pub fn extract_targets(&self) -> Vec<&str> {
+        self.members
+            .iter()
+            .chain(&self.libraries)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// `asset` followed by `fallback_assets`, in priority order.
+    pub fn asset_patterns(&self) -> Vec<&str> {
+        std::iter::once(self.asset.as_str())
+            .chain(self.fallback_assets.iter().map(String::as_str))
+            .collect()
+    }
+
+    pub fn excluded_assets(&self) -> Vec<&str> {
+        self.exclude_assets.iter().map(String::as_str).collect()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "kebab-case",
+    rename_all_fields = "camelCase"
+)]
+pub enum SourceSpec {
+    /// Fixed URL; `{asset}` is replaced by the platform asset name.
+    Direct { url: String, checksum: ChecksumSpec },
+    /// Asset of a GitHub release whose name matches the platform asset pattern.
+    GithubRelease {
+        repo: String,
+        /// Release tag; the latest release when omitted.
+        #[serde(default)]
+        tag: Option<String>,
+        /// URL returning a version to use as the tag when the release has no matching asset.
+        #[serde(default)]
+        tag_url: Option<String>,
+        checksum: ChecksumSpec,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "kebab-case",
+    rename_all_fields = "camelCase"
+)]
+pub enum ChecksumSpec {
+    /// Checksum file (a sums list or a single digest); `{asset}` is replaced as for URLs.
+    File { url: String },
+    /// The release asset `digest`, then the release notes, then `fallback_url` when set.
+    GithubDigest {
+        #[serde(default)]
+        fallback_url: Option<String>,
+    },
+}
+
+/// URL segment GitHub redirects to the newest release; swapped for a tag when pinning.
+const LATEST_DOWNLOAD_SEGMENT: &str = "/releases/latest/download/";
+
+impl SourceSpec {
+    /// `owner/name` of the GitHub repository this source downloads releases from.
+    pub fn github_repo(&self) -> Option<String> {
+        match self {
+            SourceSpec::GithubRelease { repo, .. } => Some(repo.clone()),
+            SourceSpec::Direct { url, .. } => {
+                let path = url.strip_prefix("https://github.com/")?;
+                let (repo, _) = path.split_once("/releases/")?;
+                Some(repo.to_string())
+            }
+        }
+    }
+
+    /// The same source fixed to release `tag` instead of the latest one.
+    fn pinned_to(&self, tag: &str) -> Result<SourceSpec, String> {
+        let pin_url = |url: &str| {
+            url.replace(
+                LATEST_DOWNLOAD_SEGMENT,
+                &format!("/releases/download/{}/", tag),
+            )
+        };
+        match self {
+            SourceSpec::GithubRelease { repo, checksum, .. } => Ok(SourceSpec::GithubRelease {
+                repo: repo.clone(),
+                tag: Some(tag.to_string()),
+                tag_url: None,
+                checksum: checksum.clone(),
+            }),
+            SourceSpec::Direct { url, checksum } if url.contains(LATEST_DOWNLOAD_SEGMENT) => {
+                let checksum = match checksum {
+                    ChecksumSpec::File { url } => ChecksumSpec::File { url: pin_url(url) },
+                    other => other.clone(),
+                };
+                Ok(SourceSpec::Direct {
+                    url: pin_url(url),
+                    checksum,
+                })
+            }
+            SourceSpec::Direct { url, .. } => Err(format!(
+                "{} always serves the newest build and cannot install {}",
+                url, tag
+            )),
+        }
+    }
+}
+
+/// One download location for a tool payload and where its expected SHA-256 comes from.
+pub struct ToolSource {
+    pub url: String,
+    pub checksum: ExpectedChecksum,
+}
+
+impl ToolSource {
+    pub fn new(url: impl Into<String>, checksum: ExpectedChecksum) -> Self {
+        Self {
+            url: url.into(),
+            checksum,
+        }
+    }
+}
+
+/// Concrete sources for one tool on this platform, in the order they should be tried.
+pub struct ToolInstallPlan {
+    pub asset: PlatformAsset,
+    pub sources: Vec<ToolSource>,
+}
+
+impl ToolSourceRegistry {
+    pub fn tool(&self, tool: &str) -> Result<&ToolSourceSpec, String> {
+        self.tools
+            .get(tool)
+            .ok_or_else(|| format!("Unknown tool: {}", tool))
+    }
+}
+
+impl ToolSourceSpec {
+    /// Whether the registry lists any build for the OS and architecture this build runs on.
+    pub fn available_here(&self) -> bool {
+        let (os, arch) = (std::env::consts::OS, std::env::consts::ARCH);
+        self.platforms
+            .iter()
+            .any(|platform| platform.os == os && platform.arch == arch)
+    }
+
+    /// Asset entry for the OS and architecture this build runs on: the one for `variant`,
+    /// or the platform's first (default) entry.
+    pub fn platform_asset(
+        &self,
+        tool: &str,
+        variant: Option<&str>,
+    ) -> Result<&PlatformAsset, String> {
+        let (os, arch) = (std::env::consts::OS, std::env::consts::ARCH);
+        if !self.available_here() {
+            return Err(format!(
+                "{} is not available on this platform ({}/{}); install it with the system \
+                 package manager and point HalalDL at it",
+                tool, os, arch
+            ));
+        }
+        let mut candidates = self
+            .platforms
+            .iter()
+            .filter(|platform| platform.os == os && platform.arch == arch);
+        match variant {
+            None => candidates
+                .next()
+                .ok_or_else(|| format!("No {} build is available for {}/{}", tool, os, arch)),
+            Some(variant) => candidates
+                .find(|platform| platform.variant.as_deref() == Some(variant))
+                .ok_or_else(|| {
+                    format!(
+                        "No {} {} build is available for {}/{}",
+                        tool, variant, os, arch
+                    )
+                }),
+        }
+    }
+
+    /// Sources for `channel` on `platform`, falling back to `stable`.
+    pub fn channel_sources<'a>(
+        &'a self,
+        tool: &str,
+        platform: &'a PlatformAsset,
+        channel: Option<&str>,
+    ) -> Result<&'a [SourceSpec], String> {
+        let channels = if platform.channels.is_empty() {
+            &self.channels
+        } else {
+            &platform.channels
+        };
+        channel
+            .and_then(|name| channels.get(name))
+            .or_else(|| channels.get(DEFAULT_CHANNEL))
+            .filter(|sources| !sources.is_empty())
+            .map(Vec::as_slice)
+            .ok_or_else(|| format!("No download sources are listed for {}", tool))
+    }
+}
+
+fn parse_registry(text: &str, origin: &str) -> Result<ToolSourceRegistry, String> {
+    let registry: ToolSourceRegistry = serde_json::from_str(text)
+        .map_err(|e| format!("Invalid tool source registry {}: {}", origin, e))?;
+    if registry.version != SUPPORTED_REGISTRY_VERSION {
+        return Err(format!(
+            "Tool source registry {} has version {}; this build supports version {}",
+            origin, registry.version, SUPPORTED_REGISTRY_VERSION
+        ));
+    }
+    Ok(registry)
+}
+
+pub fn tool_sources_override_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let paths = resolve_paths(app_handle)?;
+    Ok(PathBuf::from(paths.state_dir).join(OVERRIDE_FILE_NAME))
+}
+
+/// The bundled registry with any tools from the state-dir override replacing their entries.
+/// A present but unreadable override is an error rather than silently ignored.
+pub fn load_tool_sources(app_handle: &tauri::AppHandle) -> Result<ToolSourceRegistry, String> {
+    let mut registry = parse_registry(BUNDLED_REGISTRY, "(bundled)")?;
+    let override_path = tool_sources_override_path(app_handle)?;
+    if override_path.exists() {
+        let origin = override_path.display().to_string();
+        let text = fs::read_to_string(&override_path)
+            .map_err(|e| format!("Failed to read {}: {}", origin, e))?;
+        registry.tools.extend(parse_registry(&text, &origin)?.tools);
+    }
+    Ok(registry)
+}
+
+fn expand(template: &str, asset: &str) -> String {
+    template.replace("{asset}", asset)
+}
+
+async fn resolve_source(
+    app_handle: &tauri::AppHandle,
+    source: &SourceSpec,
+    asset: &PlatformAsset,
+) -> Result<ToolSource, String> {
+    match source {
+        SourceSpec::Direct { url, checksum } => {
+            let url = expand(url, &asset.asset);
+            let file_name = url.rsplit('/').next().unwrap_or(&asset.asset).to_string();
+            // Fixed URLs skip release scoring but must still name a build for this machine.
+            let host = HostPlatform::current();
+            if score_asset(&file_name, host, &[file_name.as_str()]).is_none() {
+                return Err(format!(
+                    "{} is not a {}/{} build",
+                    file_name, host.os, host.arch
+                ));
+            }
+            let checksum = match checksum {
+                ChecksumSpec::File { url: sums_url } => {
+                    ExpectedChecksum::remote(expand(sums_url, &asset.asset), &file_name)
+                }
+                ChecksumSpec::GithubDigest { .. } => {
+                    return Err(format!("{} is not a GitHub release asset", url));
+                }
+            };
+            Ok(ToolSource::new(url, checksum))
+        }
+        SourceSpec::GithubRelease {
+            repo,
+            tag,
+            tag_url,
+            checksum,
+        } => {
+            let found = resolve_github_release_asset(
+                app_handle,
+                repo,
+                tag.as_deref(),
+                tag_url.as_deref(),
+                &asset.asset_patterns(),
+                &asset.excluded_assets(),
+            )
+            .await?;
+            let checksum = match (checksum, found.sha256) {
+                (ChecksumSpec::GithubDigest { .. }, Some(hash)) => ExpectedChecksum::Known(hash),
+                (
+                    ChecksumSpec::GithubDigest {
+                        fallback_url: Some(url),
+                    },
+                    None,
+                )
+                | (ChecksumSpec::File { url }, _) => {
+                    ExpectedChecksum::remote(expand(url, &found.name), &found.name)
+                }
+                (ChecksumSpec::GithubDigest { fallback_url: None }, None) => {
+                    return Err(format!("{} publishes no checksum for {}", repo, found.name));
+                }
+            };
+            Ok(ToolSource::new(found.url, checksum))
+        }
+    }
+}
+
+/// GitHub repository whose releases `tool` installs from on `channel`, for listing the
+/// versions that can be pinned.
+pub fn tool_release_repo(
+    app_handle: &tauri::AppHandle,
+    tool: &dyn ToolSpec,
+    channel: &str,
+    variant: Option<&str>,
+) -> Result<String, String> {
+    let registry = load_tool_sources(app_handle)?;
+    let spec = registry.tool(tool.source_id())?;
+    let platform = spec.platform_asset(tool.id(), variant)?;
+    spec.channel_sources(tool.id(), platform, Some(channel))?
+        .iter()
+        .find_map(SourceSpec::github_repo)
+        .or_else(|| spec.github.clone())
+        .ok_or_else(|| format!("{} is not released on GitHub", tool.id()))
+}
+
+/// Canonical variant for a user- or UI-supplied label such as `Full Build` or `shared`.
+pub fn normalize_variant(label: &str) -> Option<&'static str> {
+    let lower = label.to_ascii_lowercase();
+    // `shared` first: `full_build-shared` names both and is the shared build.
+    ["shared", "full", "essentials"]
+        .into_iter()
+        .find(|variant| lower.contains(variant))
+}
+
+/// Resolve the registry entry for `tool` into downloadable sources for this platform,
+/// for release tag `version` or the newest release. Sources that cannot be resolved are
+/// skipped as long as one remains.
+pub async fn resolve_tool_install(
+    app_handle: &tauri::AppHandle,
+    tool: &dyn ToolSpec,
+    channel: &str,
+    variant: Option<&str>,
+    version: Option<&str>,
+) -> Result<ToolInstallPlan, String> {
+    let registry = load_tool_sources(app_handle)?;
+    let spec = registry.tool(tool.source_id())?;
+    let tool = tool.id();
+    let platform = spec.platform_asset(tool, variant)?;
+
+    let mut sources = Vec::new();
+    let mut errors = Vec::new();
+    for source in spec.channel_sources(tool, platform, Some(channel))? {
+        let resolved = match version.map(|tag| source.pinned_to(tag)) {
+            Some(Ok(pinned)) => resolve_source(app_handle, &pinned, platform).await,
+            Some(Err(e)) => Err(e),
+            None => resolve_source(app_handle, source, platform).await,
+        };
+        match resolved {
+            Ok(resolved) => sources.push(resolved),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if sources.is_empty() {
+        return Err(format!(
+            "No usable {} {} download source: {}",
+            tool,
+            version.unwrap_or("latest"),
+            errors.join("; ")
+        ));
+    }
+    Ok(ToolInstallPlan {
+        asset: platform.clone(),
+        sources,
+    })
+}
@@ -0,0 +1,181 @@
+//! The tools HalalDL manages, each described once by a `ToolSpec`: its binaries and sidecars,
+//! how to probe its version and which release channels it has. Download sources live in
+//! `tool-sources.json` under the spec's `source_id`. Supporting another tool (gallery-dl,
+//! mkvmerge, ...) means adding a spec to `TOOL_SPECS` and its sources to the registry.
+
+use std::path::Path;
+
+use crate::tool_sources::DEFAULT_CHANNEL;
+use crate::tool_version::VersionFormat;
+
+pub trait ToolSpec: Send + Sync {
+    /// Id used by the frontend, the tool manifest and the backup directories.
+    fn id(&self) -> &'static str;
+
+    /// Native file names: the tool itself first, then sidecars installed alongside it.
+    fn binaries(&self) -> &'static [&'static str];
+
+    /// Arguments that make the tool print its version on the first line of stdout.
+    fn version_args(&self) -> &'static [&'static str] {
+        &["--version"]
+    }
+
+    /// How to read the version out of that line.
+    fn version_format(&self) -> VersionFormat {
+        VersionFormat::Generic
+    }
+
+    /// Release channels its sources offer; the first is the default.
+    fn channels(&self) -> &'static [&'static str] {
+        &[DEFAULT_CHANNEL]
+    }
+
+    /// Key of the tool's entry in `tool-sources.json`.
+    fn source_id(&self) -> &'static str {
+        self.id()
+    }
+
+    fn primary_binary(&self) -> &'static str {
+        self.binaries()[0]
+    }
+
+    fn sidecars(&self) -> &'static [&'static str] {
+        &self.binaries()[1..]
+    }
+
+    fn owns_binary(&self, file_name: &str) -> bool {
+        self.binaries()
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(file_name))
+    }
+
+    /// `channel` when the tool offers it, otherwise its default channel.
+    fn resolve_channel(&self, channel: Option<&str>) -> &'static str {
+        channel
+            .and_then(|name| self.channels().iter().find(|known| **known == name))
+            .copied()
+            .unwrap_or(self.channels()[0])
+    }
+
+    /// First line of the version output of the binary at `path`, if it runs.
+    fn probe_version(&self, path: &Path) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        use std::os::windows::process::CommandExt;
+
+        let mut cmd = std::process::Command::new(path);
+        cmd.args(self.version_args());
+        #[cfg(target_os = "windows")]
+        {
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let output = cmd.output().ok().filter(|output| output.status.success())?;
+        let first_line = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        Some(first_line).filter(|line| !line.is_empty())
+    }
+}
+
+/// A tool described entirely by constants.
+struct BuiltinTool {
+    id: &'static str,
+    binaries: &'static [&'static str],
+    version_args: &'static [&'static str],
+    version_format: VersionFormat,
+    channels: &'static [&'static str],
+}
+
+impl ToolSpec for BuiltinTool {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn binaries(&self) -> &'static [&'static str] {
+        self.binaries
+    }
+
+    fn version_args(&self) -> &'static [&'static str] {
+        self.version_args
+    }
+
+    fn version_format(&self) -> VersionFormat {
+        self.version_format
+    }
+
+    fn channels(&self) -> &'static [&'static str] {
+        self.channels
+    }
+}
+
+const WINDOWS: bool = cfg!(target_os = "windows");
+
+static YT_DLP: BuiltinTool = BuiltinTool {
+    id: "yt-dlp",
+    binaries: if WINDOWS {
+        &["yt-dlp.exe"]
+    } else {
+        &["yt-dlp"]
+    },
+    version_args: &["--version"],
+    version_format: VersionFormat::YtDlp,
+    channels: &["stable", "nightly"],
+};
+
+static FFMPEG: BuiltinTool = BuiltinTool {
+    id: "ffmpeg",
+    binaries: if WINDOWS {
+        &["ffmpeg.exe", "ffprobe.exe"]
+    } else {
+        &["ffmpeg", "ffprobe"]
+    },
+    version_args: &["-version"],
+    version_format: VersionFormat::Ffmpeg,
+    channels: &["stable", "nightly"],
+};
+
+static ARIA2: BuiltinTool = BuiltinTool {
+    id: "aria2",
+    binaries: if WINDOWS {
+        &["aria2c.exe"]
+    } else {
+        &["aria2c"]
+    },
+    version_args: &["--version"],
+    version_format: VersionFormat::Generic,
+    channels: &["stable"],
+};
+
+static DENO: BuiltinTool = BuiltinTool {
+    id: "deno",
+    binaries: if WINDOWS { &["deno.exe"] } else { &["deno"] },
+    version_args: &["--version"],
+    version_format: VersionFormat::Deno,
+    channels: &["stable"],
+};
+
+/// Every managed tool, in the order the app installs and lists them.
+static TOOL_SPECS: &[&dyn ToolSpec] = &[&YT_DLP, &FFMPEG, &ARIA2, &DENO];
+
+pub fn tool_specs() -> &'static [&'static dyn ToolSpec] {
+    TOOL_SPECS
+}
+
+pub fn tool_spec(id: &str) -> Result<&'static dyn ToolSpec, String> {
+    TOOL_SPECS
+        .iter()
+        .copied()
+        .find(|spec| spec.id() == id)
+        .ok_or_else(|| format!("Unknown tool: {}", id))
+}
+
+/// The tool that installs `file_name` (e.g. `ffprobe.exe` -> FFmpeg).
+pub fn tool_for_binary(file_name: &str) -> Option<&'static dyn ToolSpec> {
+    TOOL_SPECS
+        .iter()
+        .copied()
+        .find(|spec| spec.owns_binary(file_name))
+}
@@ -0,0 +1,252 @@
+//! All-or-nothing replacement of a tool's binaries (FFmpeg ships ffmpeg and ffprobe as a
+//! pair). A journal in the state dir lists every file before the first one is touched, so a
+//! swap cut short by a crash or power loss is undone on the next startup.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fs_utils::{backup_path_for, move_file};
+use crate::tool_manifest::now_ms;
+
+const JOURNAL_DIR: &str = "tool-transactions";
+
+static NEXT_JOURNAL: AtomicU32 = AtomicU32::new(0);
+
+/// Move `incoming` over `dest`.
+pub struct Replacement {
+    pub dest: PathBuf,
+    pub incoming: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalFile {
+    dest: PathBuf,
+    incoming: PathBuf,
+    had_original: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Journal {
+    label: String,
+    /// Every file is in place; only the replaced files still need filing away.
+    committed: bool,
+    /// Keep replaced files as `.old` backups (installs) instead of dropping them (rollbacks).
+    keep_replaced: bool,
+    files: Vec<JournalFile>,
+}
+
+/// Where a replaced file waits until the whole transaction has gone through.
+fn aside_path_for(dest: &Path) -> Result<PathBuf, String> {
+    let file_name = dest
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Invalid target path: {}", dest.display()))?;
+    Ok(dest.with_file_name(format!("{}.txn-old", file_name)))
+}
+
+fn journal_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let paths = crate::app_paths::resolve_paths(app_handle)?;
+    Ok(PathBuf::from(paths.state_dir).join(JOURNAL_DIR))
+}
+
+fn write_journal(path: &Path, journal: &Journal) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(journal).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Put every file of an unfinished transaction back the way it was, last file first.
+fn revert(journal: &Journal) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for file in journal.files.iter().rev() {
+        let result = (|| {
+            let aside = aside_path_for(&file.dest)?;
+            let moved_in = file.dest.exists()
+                && !file.incoming.exists()
+                && (!file.had_original || aside.exists());
+            if moved_in {
+                move_file(&file.dest, &file.incoming)
+                    .map_err(|e| format!("Failed to move {} back: {}", file.dest.display(), e))?;
+            }
+            if aside.exists() {
+                move_file(&aside, &file.dest)
+                    .map_err(|e| format!("Failed to restore {}: {}", file.dest.display(), e))?;
+            }
+            Ok::<(), String>(())
+        })();
+        if let Err(e) = result {
+            errors.push(e);
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// File away the replaced originals of a transaction whose new files are all in place.
+fn finish(journal: &Journal) -> Result<(), String> {
+    for file in &journal.files {
+        let aside = aside_path_for(&file.dest)?;
+        if !aside.exists() {
+            continue;
+        }
+        if journal.keep_replaced {
+            let backup = backup_path_for(&file.dest)?;
+            if backup.exists() {
+                let _ = fs::remove_file(&backup);
+            }
+            move_file(&aside, &backup)
+                .map_err(|e| format!("Failed to keep backup of {}: {}", file.dest.display(), e))?;
+        } else {
+            let _ = fs::remove_file(&aside);
+        }
+    }
+    Ok(())
+}
+
+fn apply(journal: &Journal) -> Result<(), String> {
+    for file in &journal.files {
+        if file.had_original {
+            let aside = aside_path_for(&file.dest)?;
+            move_file(&file.dest, &aside).map_err(|e| {
+                format!(
+                    "Failed to move current {} aside: {}",
+                    file.dest.display(),
+                    e
+                )
+            })?;
+        }
+        move_file(&file.incoming, &file.dest).map_err(|e| {
+            format!(
+                "Failed to activate new file {} -> {}: {}",
+                file.incoming.display(),
+                file.dest.display(),
+                e
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Swap in every replacement or none of them. Replaced files become `.old` backups when
+/// `keep_replaced` is set and are deleted otherwise; on failure every file is put back.
+pub fn replace_all(
+    app_handle: &tauri::AppHandle,
+    label: &str,
+    replacements: Vec<Replacement>,
+    keep_replaced: bool,
+) -> Result<(), String> {
+    let mut journal = Journal {
+        label: label.to_string(),
+        committed: false,
+        keep_replaced,
+        files: replacements
+            .into_iter()
+            .map(|replacement| JournalFile {
+                had_original: replacement.dest.exists(),
+                dest: replacement.dest,
+                incoming: replacement.incoming,
+            })
+            .collect(),
+    };
+    for file in &journal.files {
+        if !file.incoming.is_file() {
+            return Err(format!("Missing staged file {}", file.incoming.display()));
+        }
+        // A stray leftover would be taken for this transaction's original on revert.
+        let aside = aside_path_for(&file.dest)?;
+        if aside.exists() {
+            let _ = fs::remove_file(&aside);
+        }
+    }
+
+    let dir = journal_dir(app_handle)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let journal_path = dir.join(format!(
+        "{}-{}.json",
+        now_ms(),
+        NEXT_JOURNAL.fetch_add(1, Ordering::Relaxed)
+    ));
+    write_journal(&journal_path, &journal)?;
+
+    if let Err(e) = apply(&journal) {
+        let outcome = match revert(&journal) {
+            Ok(()) => e,
+            // Keep the journal so startup repair can try again.
+            Err(revert_error) => {
+                return Err(format!("{} (restore also failed: {})", e, revert_error));
+            }
+        };
+        let _ = fs::remove_file(&journal_path);
+        return Err(outcome);
+    }
+
+    journal.committed = true;
+    if let Err(e) = write_journal(&journal_path, &journal) {
+        eprintln!("[tools] Warning: {}", e);
+    }
+    match finish(&journal) {
+        Ok(()) => {
+            let _ = fs::remove_file(&journal_path);
+        }
+        Err(e) => eprintln!("[tools] Warning: {} swapped in but {}", label, e),
+    }
+    Ok(())
+}
+
+/// Undo (or, when every file was already swapped, finish) transactions a previous run left
+/// behind. Returns one line per transaction dealt with.
+pub fn repair_interrupted(app_handle: &tauri::AppHandle) -> Vec<String> {
+    let Ok(dir) = journal_dir(app_handle) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut repaired = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let journal: Journal = match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()))
+        {
+            Ok(journal) => journal,
+            Err(e) => {
+                eprintln!(
+                    "[tools] Warning: unreadable transaction journal {}: {}",
+                    path.display(),
+                    e
+                );
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+        };
+
+        let (result, action) = if journal.committed {
+            (finish(&journal), "completed")
+        } else {
+            (revert(&journal), "rolled back")
+        };
+        match result {
+            Ok(()) => {
+                let _ = fs::remove_file(&path);
+                repaired.push(format!("Interrupted {} update {}", journal.label, action));
+            }
+            Err(e) => repaired.push(format!(
+                "Interrupted {} update could not be repaired: {}",
+                journal.label, e
+            )),
+        }
+    }
+    repaired
+}
@@ -0,0 +1,275 @@
+//! Parsing of what each tool prints for its version into a comparable form, so the backend
+//! can tell whether an installed or system tool is behind the latest release:
+//!
+//! - yt-dlp: `2025.01.15` (nightly builds add a time: `2025.01.16.232808`)
+//! - ffmpeg: `ffmpeg version n7.1-essentials_build-www.gyan.dev ...`, or a dated git build
+//! - aria2: `aria2 version 1.37.0`
+//! - deno: `deno 2.1.4 (stable, release, x86_64-pc-windows-msvc)`
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::tool_specs::{tool_for_binary, tool_spec, ToolSpec};
+use crate::version::latest_tool_version;
+
+/// How a tool formats its version line; see the module docs for examples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionFormat {
+    /// First version-looking token of the line.
+    Generic,
+    /// Calendar version, with a build time on nightlies.
+    YtDlp,
+    /// `version <tag>` with build flavor and git/date suffixes.
+    Ffmpeg,
+    /// `<name> <version> (<channel>, ...)`.
+    Deno,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolVersion {
+    /// The line the version was parsed from.
+    pub raw: String,
+    /// Dotted version, e.g. `7.1`, `2025.01.15` or `1.37.0`.
+    pub version: String,
+    /// `stable` or `nightly` (git, canary and dated builds).
+    pub channel: String,
+    /// Build flavor such as `essentials`, `full`, `shared` or `static`.
+    pub flavor: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolVersionStatus {
+    pub tool: String,
+    pub path: String,
+    pub installed: Option<ToolVersion>,
+    pub latest: Option<ToolVersion>,
+    /// `None` when either side is unknown or they are on different channels.
+    pub outdated: Option<bool>,
+    /// Why the latest version could not be fetched.
+    pub latest_error: Option<String>,
+}
+
+/// Token after the word `version`, or the first token when there is none.
+fn version_token(line: &str) -> Option<&str> {
+    let mut tokens = line.split_whitespace();
+    let labelled = line
+        .split_whitespace()
+        .position(|token| token.eq_ignore_ascii_case("version"));
+    match labelled {
+        Some(index) => tokens.nth(index + 1),
+        None => tokens.next(),
+    }
+}
+
+/// Leading `1.2.3` of `token`, without a `v`/`n` prefix.
+fn leading_dotted(token: &str) -> Option<String> {
+    let token = token.trim_start_matches(['v', 'V', 'n']);
+    let end = token
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(token.len());
+    let dotted = token[..end].trim_end_matches('.');
+    dotted
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| dotted.to_string())
+}
+
+/// Build date of an FFmpeg git build as `YYYY.MM.DD`: gyan.dev tags start with
+/// `YYYY-MM-DD-git-`, BtbN tags look like `N-118315-g4f3c9f2f03-20250115`.
+fn ffmpeg_build_date(token: &str) -> Option<String> {
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let mut parts = token.splitn(4, '-');
+    if let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) {
+        if year.len() == 4
+            && month.len() == 2
+            && day.len() == 2
+            && digits(year)
+            && digits(month)
+            && digits(day)
+        {
+            return Some(format!("{}.{}.{}", year, month, day));
+        }
+    }
+    if !token.starts_with("N-") {
+        return None;
+    }
+    token
+        .split('-')
+        .find(|segment| segment.len() == 8 && digits(segment))
+        .map(|date| format!("{}.{}.{}", &date[..4], &date[4..6], &date[6..]))
+}
+
+fn ffmpeg_flavor(token: &str) -> Option<String> {
+    let lower = token.to_ascii_lowercase();
+    ["essentials", "shared", "full", "static"]
+        .into_iter()
+        .find(|flavor| lower.contains(flavor))
+        .map(str::to_string)
+}
+
+/// Parse the first non-empty line of a tool's version output.
+pub fn parse_version(format: VersionFormat, output: &str) -> Option<ToolVersion> {
+    let line = output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    let stable = |version: String, flavor: Option<String>| ToolVersion {
+        raw: line.to_string(),
+        version,
+        channel: "stable".to_string(),
+        flavor,
+    };
+
+    match format {
+        VersionFormat::Generic => line
+            .split_whitespace()
+            .find_map(leading_dotted)
+            .map(|version| stable(version, None)),
+        VersionFormat::YtDlp => {
+            let version = leading_dotted(version_token(line)?)?;
+            // Nightly builds append the build time (HHMMSS) as a fourth part.
+            let nightly = version
+                .split('.')
+                .nth(3)
+                .is_some_and(|part| part.len() >= 6);
+            let mut parsed = stable(version, None);
+            if nightly {
+                parsed.channel = "nightly".to_string();
+            }
+            Some(parsed)
+        }
+        VersionFormat::Ffmpeg => {
+            let token = version_token(line)?;
+            let flavor = ffmpeg_flavor(token);
+            match ffmpeg_build_date(token) {
+                Some(date) => Some(ToolVersion {
+                    raw: line.to_string(),
+                    version: date,
+                    channel: "nightly".to_string(),
+                    flavor,
+                }),
+                None => leading_dotted(token).map(|version| stable(version, flavor)),
+            }
+        }
+        VersionFormat::Deno => {
+            // `deno 2.1.4+1a2b3c4 (canary, ...)` on canary builds.
+            let token = line
+                .split_whitespace()
+                .find(|token| leading_dotted(token).is_some())?;
+            let mut parsed = stable(leading_dotted(token)?, None);
+            if token.contains('+') || line.contains("canary") {
+                parsed.channel = "nightly".to_string();
+            }
+            Some(parsed)
+        }
+    }
+}
+
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+/// Order two parsed versions; `None` when they are on different channels.
+pub fn compare_versions(a: &ToolVersion, b: &ToolVersion) -> Option<Ordering> {
+    if a.channel != b.channel {
+        return None;
+    }
+    let (a, b) = (version_parts(&a.version), version_parts(&b.version));
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    let len = a.len().max(b.len());
+    let padded = |parts: &[u64], i: usize| parts.get(i).copied().unwrap_or(0);
+    Some(
+        (0..len)
+            .map(|i| padded(&a, i).cmp(&padded(&b, i)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal),
+    )
+}
+
+/// Run the binary at `path` and parse its version the way `spec` prints it.
+pub fn probe_parsed_version(spec: &dyn ToolSpec, path: &Path) -> Option<ToolVersion> {
+    let output = spec.probe_version(path)?;
+    parse_version(spec.version_format(), &output)
+}
+
+fn executable_path(path: &str) -> Result<PathBuf, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("Executable path is empty".to_string());
+    }
+    Ok(PathBuf::from(trimmed))
+}
+
+/// Version of a managed or system tool binary (`ffprobe` counts as FFmpeg), parsed into
+/// version, channel and flavor. `None` when it does not exist or does not run.
+#[tauri::command]
+pub fn probe_tool_version(path: String) -> Result<Option<ToolVersion>, String> {
+    let path = executable_path(&path)?;
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    let spec = tool_for_binary(file_name)
+        .ok_or_else(|| format!("{} is not a known tool binary", file_name))?;
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(probe_parsed_version(spec, &path))
+}
+
+/// Compare the `tool` binary at `path` (the app-managed one by default) with the latest
+/// release on `channel`, as reported by `version::fetch_latest_*` (pins included).
+#[tauri::command]
+pub async fn check_tool_version(
+    app_handle: tauri::AppHandle,
+    tool: String,
+    path: Option<String>,
+    channel: Option<String>,
+) -> Result<ToolVersionStatus, String> {
+    let spec = tool_spec(&tool)?;
+    let path = match path {
+        Some(path) => executable_path(&path)?,
+        None => {
+            let paths = crate::app_paths::resolve_paths(&app_handle)?;
+            PathBuf::from(paths.bin_dir).join(spec.primary_binary())
+        }
+    };
+
+    let probe_path = path.clone();
+    let installed = tauri::async_runtime::spawn_blocking(move || {
+        probe_path
+            .is_file()
+            .then(|| probe_parsed_version(spec, &probe_path))
+            .flatten()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let channel = spec.resolve_channel(channel.as_deref());
+    let (latest, latest_error) = match latest_tool_version(&app_handle, spec.id(), channel).await {
+        Ok(latest) => (parse_version(spec.version_format(), &latest), None),
+        Err(e) => (None, Some(e)),
+    };
+    let outdated = installed
+        .as_ref()
+        .zip(latest.as_ref())
+        .and_then(|(installed, latest)| compare_versions(installed, latest))
+        .map(Ordering::is_lt);
+
+    Ok(ToolVersionStatus {
+        tool,
+        path: path.to_string_lossy().to_string(),
+        installed,
+        latest,
+        outdated,
+        latest_error,
+    })
+}
@@ -1,32 +1,30 @@
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
-use crate::download::{
-    download_to_temp, emit_progress, resolve_latest_aria2_zip_url,
-    resolve_latest_ffmpeg_essentials_zip_url,
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use crate::archive_limits::{ensure_disk_space, rejection_kind, ArchiveLimitError, MAX_ENTRY_SIZE};
+use crate::checksums::{resolve_expected_sha256, verify_sha256};
+use crate::download::{discard_partial, download_to_temp, emit_progress, sha256_of_path};
+use crate::extract::{archive_extension, extract_archive};
+use crate::fs_utils::{backup_path_for, make_executable, safe_replace_with_backup, temp_path_for};
+use crate::tool_backups::{
+    archive_replaced, list_generation_backups, remove_generations, restore_generation, ToolBackup,
 };
-use crate::extract::extract_from_zip;
-use crate::fs_utils::{safe_replace_with_backup, temp_path_for};
-
-fn system_tool_bin_name(tool: &str) -> Result<&'static str, String> {
-    #[cfg(target_os = "windows")]
-    let bin_name = match tool {
-        "yt-dlp" => "yt-dlp.exe",
-        "ffmpeg" => "ffmpeg.exe",
-        "aria2" => "aria2c.exe",
-        "deno" => "deno.exe",
-        _ => return Err(format!("Unknown tool: {}", tool)),
-    };
-    #[cfg(not(target_os = "windows"))]
-    let bin_name = match tool {
-        "yt-dlp" => "yt-dlp",
-        "ffmpeg" => "ffmpeg",
-        "aria2" => "aria2c",
-        "deno" => "deno",
-        _ => return Err(format!("Unknown tool: {}", tool)),
-    };
-    Ok(bin_name)
-}
+use crate::tool_locks::{lock_tool_files, try_lock_tool_files, ToolFilesGuard};
+use crate::tool_manifest::{
+    forget_tool, record_install, record_rollback, recorded_entry, InstallMethod, ToolManifestEntry,
+};
+use crate::tool_pins::{check_version_tag, pinned_version, set_pin, LATEST_VERSION};
+use crate::tool_preflight::plan_tool_update;
+use crate::tool_sources::{load_tool_sources, normalize_variant, resolve_tool_install, ToolSource};
+use crate::tool_specs::{tool_for_binary, tool_spec, tool_specs, ToolSpec};
+use crate::tool_transaction::{replace_all, Replacement};
+use crate::transfers::{
+    begin_transfer, check_cancelled, is_cancelled_error, or_cancelled, transfer_token,
+    CANCELLED_ERROR,
+};
+use crate::ytdlp_install::{detect_ytdlp_install, explain_upgrade_error};
 
 fn unique_tool_paths(stdout: &str) -> Vec<String> {
     let mut seen = HashSet::new();
@@ -45,7 +43,7 @@ fn unique_tool_paths(stdout: &str) -> Vec<String> {
 }
 
 fn collect_system_tool_paths(tool: &str) -> Result<Vec<String>, String> {
-    let bin_name = system_tool_bin_name(tool)?;
+    let bin_name = tool_spec(tool)?.primary_binary();
 
     #[cfg(target_os = "windows")]
     {
@@ -101,7 +99,8 @@ pub fn resolve_system_tool_paths(tool: String) -> Result<Vec<String>, String> {
     collect_system_tool_paths(&tool)
 }
 
-/// Cheap `--version` probe for a specific executable (used to label Lite Deno picker rows).
+/// Cheap version probe for a specific executable of any managed tool (used to label Lite
+/// Deno picker rows); returns the first line of its version output.
 #[tauri::command]
 pub fn probe_executable_version(path: String) -> Result<Option<String>, String> {
     let trimmed = path.trim();
@@ -113,43 +112,13 @@ pub fn probe_executable_version(path: String) -> Result<Option<String>, String>
     let file_name = file_path
         .file_name()
         .and_then(|name| name.to_str())
-        .unwrap_or("")
-        .to_ascii_lowercase();
-    if file_name != "deno" && file_name != "deno.exe" {
-        return Err("Only deno executables can be version-probed this way".to_string());
-    }
+        .unwrap_or("");
+    let spec = tool_for_binary(file_name)
+        .ok_or_else(|| format!("{} is not a known tool binary", file_name))?;
     if !file_path.is_file() {
         return Ok(None);
     }
-
-    #[cfg(target_os = "windows")]
-    use std::os::windows::process::CommandExt;
-
-    let mut cmd = std::process::Command::new(trimmed);
-    cmd.arg("--version");
-    #[cfg(target_os = "windows")]
-    {
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
-
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to run '{} --version': {}", trimmed, e))?;
-    if !output.status.success() {
-        return Ok(None);
-    }
-
-    let first_line = String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .next()
-        .unwrap_or("")
-        .trim()
-        .to_string();
-    if first_line.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(first_line))
-    }
+    Ok(spec.probe_version(file_path))
 }
 
 fn run_quiet(program: &Path, args: &[&str]) -> Result<(), String> {
@@ -178,76 +147,121 @@ fn run_quiet(program: &Path, args: &[&str]) -> Result<(), String> {
     Err(format!("{} failed: {}", program.display(), detail))
 }
 
-/// Upgrade pip-installed yt-dlp. Prefers pip/python beside the active binary.
-#[tauri::command]
-pub fn upgrade_ytdlp_via_pip(system_path: Option<String>) -> Result<String, String> {
-    let mut candidates: Vec<(PathBuf, Vec<&'static str>)> = Vec::new();
-
-    if let Some(raw) = system_path.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
-        let ytdlp = PathBuf::from(raw);
-        let lower = ytdlp.to_string_lossy().to_ascii_lowercase();
-        if lower.contains("\\scripts\\") || lower.contains("/scripts/") {
-            if let Some(scripts) = ytdlp.parent() {
-                #[cfg(target_os = "windows")]
-                let pip = scripts.join("pip.exe");
-                #[cfg(not(target_os = "windows"))]
-                let pip = scripts.join("pip");
-                if pip.is_file() {
-                    candidates.push((pip, vec!["install", "--upgrade", "yt-dlp"]));
-                }
-
-                #[cfg(target_os = "windows")]
-                let python = scripts.parent().map(|p| p.join("python.exe"));
-                #[cfg(not(target_os = "windows"))]
-                let python = scripts.parent().map(|p| p.join("python"));
-                if let Some(python) = python.filter(|p| p.is_file()) {
-                    candidates.push((python, vec!["-m", "pip", "install", "--upgrade", "yt-dlp"]));
-                }
-            }
-        }
+/// `system_path` when given, otherwise the first yt-dlp on PATH.
+pub fn system_ytdlp_path(system_path: Option<&str>) -> Result<PathBuf, String> {
+    if let Some(raw) = system_path.map(str::trim).filter(|s| !s.is_empty()) {
+        return Ok(PathBuf::from(raw));
     }
+    collect_system_tool_paths("yt-dlp")?
+        .into_iter()
+        .next()
+        .map(PathBuf::from)
+        .ok_or_else(|| "yt-dlp was not found on PATH".to_string())
+}
 
-    candidates.push((PathBuf::from("pip"), vec!["install", "--upgrade", "yt-dlp"]));
-    candidates.push((PathBuf::from("pip3"), vec!["install", "--upgrade", "yt-dlp"]));
-
-    let mut last_err = String::from("no pip candidates");
-    for (program, args) in candidates {
-        match run_quiet(&program, &args) {
-            Ok(()) => {
-                return Ok(format!(
-                    "yt-dlp upgraded via {} {}",
-                    program.display(),
-                    args.join(" ")
-                ));
-            }
-            Err(e) => last_err = e,
-        }
-    }
-    Err(format!("pip upgrade failed: {}", last_err))
+/// Upgrade a system yt-dlp with whatever installed it (pip, pipx, uv tool, conda, Homebrew).
+/// Refuses distro packages and installs it cannot identify rather than guessing a pip.
+#[tauri::command]
+pub async fn upgrade_ytdlp_via_pip(
+    app_handle: tauri::AppHandle,
+    system_path: Option<String>,
+) -> Result<String, String> {
+    // The package manager can run for minutes and detection reads files, so both stay off
+    // the async runtime.
+    let (ytdlp, install, command_line) = tauri::async_runtime::spawn_blocking(move || {
+        let ytdlp = system_ytdlp_path(system_path.as_deref())?;
+        let install = detect_ytdlp_install(&ytdlp);
+        let Some((program, args)) = install
+            .upgrade_command
+            .as_deref()
+            .and_then(|command| command.split_first())
+        else {
+            return Err(install
+                .reason
+                .clone()
+                .unwrap_or_else(|| format!("Cannot upgrade {}", ytdlp.display())));
+        };
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_quiet(Path::new(program), &args).map_err(|e| explain_upgrade_error(&install, e))?;
+        let command_line = format!("{} {}", program, args.join(" "));
+        Ok((ytdlp, install, command_line))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let mut entry =
+        ToolManifestEntry::new("yt-dlp", ytdlp.parent(), install.method.manifest_method());
+    entry.source_url = Some(command_line.clone());
+    record_install(&app_handle, entry);
+    Ok(format!("yt-dlp upgraded via {}", command_line))
 }
 
+/// Download `source` next to `dest_path`, verify its SHA-256 and only then activate it.
+/// Returns the verified digest.
 async fn download_tool_payload(
     app_handle: &tauri::AppHandle,
     tool: &str,
-    url: &str,
+    source: &ToolSource,
     dest_path: &PathBuf,
-) -> Result<(), String> {
-    let temp = download_to_temp(app_handle, tool, url, dest_path).await?;
+) -> Result<String, String> {
+    // Resolve the digest first so an unverifiable source fails before any download.
+    let expected = resolve_expected_sha256(app_handle, &source.checksum).await?;
+    let temp = download_to_temp(app_handle, tool, &source.url, dest_path).await?;
+    if let Err(e) = check_cancelled(app_handle, tool) {
+        discard_partial(&temp);
+        return Err(e);
+    }
+
+    emit_progress(app_handle, tool, 100.0, "Verifying SHA-256...");
+    let label = dest_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| tool.to_string());
+    // Hashing a large archive would stall the async worker it runs on.
+    let hash_path = temp.clone();
+    let verified =
+        tauri::async_runtime::spawn_blocking(move || verify_sha256(&hash_path, &expected, &label))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|verified| verified);
+    let verified = match verified {
+        Ok(hash) => hash,
+        Err(e) => {
+            discard_partial(&temp);
+            return Err(e);
+        }
+    };
     safe_replace_with_backup(dest_path, &temp)?;
-    Ok(())
+    Ok(verified)
+}
+
+/// A download that passed SHA-256 verification, and the source it came from.
+struct VerifiedPayload {
+    url: String,
+    sha256: String,
+    /// Files extracted from an archive download; empty for single-file downloads.
+    extracted: Vec<String>,
 }
 
 async fn download_tool_payload_from_sources(
     app_handle: &tauri::AppHandle,
     tool: &str,
-    sources: &[String],
+    sources: &[ToolSource],
     dest_path: &PathBuf,
-) -> Result<(), String> {
+) -> Result<VerifiedPayload, String> {
     let mut last_error: Option<String> = None;
 
-    for (index, url) in sources.iter().enumerate() {
-        match download_tool_payload(app_handle, tool, url, dest_path).await {
-            Ok(()) => return Ok(()),
+    for (index, source) in sources.iter().enumerate() {
+        match download_tool_payload(app_handle, tool, source, dest_path).await {
+            Ok(sha256) => {
+                return Ok(VerifiedPayload {
+                    url: source.url.clone(),
+                    sha256,
+                    extracted: Vec::new(),
+                })
+            }
+            Err(e) if is_cancelled_error(&e) => return Err(e),
             Err(e) => {
                 last_error = Some(e);
                 if index + 1 < sources.len() {
@@ -269,102 +283,216 @@ async fn download_tool_payload_from_sources(
     Err(last_error.unwrap_or_else(|| format!("{} download failed", tool)))
 }
 
-async fn download_ytdlp(
-    app_handle: &tauri::AppHandle,
-    dest: &PathBuf,
-    is_nightly: bool,
-) -> Result<(), String> {
-    let url = if is_nightly {
-        "https://github.com/yt-dlp/yt-dlp-nightly-builds/releases/latest/download/yt-dlp.exe"
-    } else {
-        "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp.exe"
-    };
-    let dest_file = dest.join("yt-dlp.exe");
-    download_tool_payload(app_handle, "yt-dlp", url, &dest_file).await
+/// Remove a downloaded archive together with its `.new`, resume and `.old` leftovers.
+fn remove_archive_artifacts(archive_path: &Path) {
+    let _ = fs::remove_file(archive_path);
+    if let Ok(temp) = temp_path_for(archive_path) {
+        discard_partial(&temp);
+    }
+    if let Ok(backup) = backup_path_for(archive_path) {
+        let _ = fs::remove_file(backup);
+    }
 }
 
-async fn download_ffmpeg(
+/// Download an archive from the first working source and extract `targets` into `dest`.
+/// The archive is removed afterwards; on cancellation every partial artifact goes too.
+async fn install_from_archive(
     app_handle: &tauri::AppHandle,
-    dest: &PathBuf,
-    _variant: Option<String>,
-    is_nightly: bool,
-) -> Result<(), String> {
-    // Essentials only — Full/Shared variants are not shipped by HalalDL.
-    let mut sources = Vec::new();
-    if is_nightly {
-        sources.push("https://www.gyan.dev/ffmpeg/builds/ffmpeg-git-essentials.zip".to_string());
-    } else {
-        let mirror_url = resolve_latest_ffmpeg_essentials_zip_url(app_handle)
-            .await
-            .ok();
-        if let Some(url) = mirror_url {
-            sources.push(url);
+    tool: &str,
+    sources: &[ToolSource],
+    archive_path: &PathBuf,
+    dest: &Path,
+    targets: Vec<&str>,
+) -> Result<VerifiedPayload, String> {
+    let downloaded = download_tool_payload_from_sources(app_handle, tool, sources, archive_path)
+        .await
+        .and_then(|payload| check_cancelled(app_handle, tool).map(|()| payload));
+    let mut verified = match downloaded {
+        Ok(payload) => payload,
+        Err(e) => {
+            if is_cancelled_error(&e) {
+                remove_archive_artifacts(archive_path);
+            }
+            return Err(e);
         }
-        sources.push("https://www.gyan.dev/ffmpeg/builds/ffmpeg-release-essentials.zip".to_string());
-    }
+    };
 
-    let zip_path = dest.join("ffmpeg-update.zip");
-    download_tool_payload_from_sources(app_handle, "ffmpeg", &sources, &zip_path).await?;
     emit_progress(
         app_handle,
-        "ffmpeg",
-        99.0,
-        "Extracting ffmpeg.exe, ffprobe.exe from zip...",
+        tool,
+        0.0,
+        &format!("Extracting {} from archive...", targets.join(", ")),
     );
-    let extracted = extract_from_zip(
-        app_handle,
-        "ffmpeg",
-        &zip_path,
-        dest,
-        vec!["ffmpeg.exe", "ffprobe.exe"],
-    )?;
+    let (extract_app, extract_tool) = (app_handle.clone(), tool.to_string());
+    let (extract_from, extract_to) = (archive_path.clone(), dest.to_path_buf());
+    let targets: Vec<String> = targets.into_iter().map(str::to_string).collect();
+    let extracted = tauri::async_runtime::spawn_blocking(move || {
+        let targets = targets.iter().map(String::as_str).collect();
+        extract_archive(
+            &extract_app,
+            &extract_tool,
+            &extract_from,
+            &extract_to,
+            targets,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())??;
     emit_progress(
         app_handle,
-        "ffmpeg",
+        tool,
         100.0,
         &format!("Extracted: {}", extracted.join(", ")),
     );
-    if let Err(e) = fs::remove_file(&zip_path) {
-        eprintln!("[tools] Warning: failed to clean up {:?}: {}", zip_path, e);
+    if let Err(e) = fs::remove_file(archive_path) {
+        eprintln!(
+            "[tools] Warning: failed to clean up {:?}: {}",
+            archive_path, e
+        );
     }
-    Ok(())
+    verified.extracted = extracted;
+    Ok(verified)
 }
 
-async fn download_aria2(app_handle: &tauri::AppHandle, dest: &PathBuf) -> Result<(), String> {
-    let url = resolve_latest_aria2_zip_url(app_handle).await?;
-    let zip_path = dest.join("aria2-update.zip");
-    download_tool_payload(app_handle, "aria2", &url, &zip_path).await?;
-    emit_progress(app_handle, "aria2", 99.0, "Extracting aria2c.exe...");
-    let extracted = extract_from_zip(app_handle, "aria2", &zip_path, dest, vec!["aria2c.exe"])?;
-    emit_progress(
-        app_handle,
-        "aria2",
-        100.0,
-        &format!("Extracted: {}", extracted.join(", ")),
-    );
-    if let Err(e) = fs::remove_file(&zip_path) {
-        eprintln!("[tools] Warning: failed to clean up {:?}: {}", zip_path, e);
+fn is_app_bin_dir(app_handle: &tauri::AppHandle, dir: &Path) -> bool {
+    crate::app_paths::resolve_paths(app_handle)
+        .map(|paths| Path::new(&paths.bin_dir) == dir)
+        .unwrap_or(false)
+}
+
+/// Record a finished install in the tool manifest. In the app bin dir the `.old` files it
+/// left behind become a versioned backup generation.
+pub fn finish_install(app_handle: &tauri::AppHandle, dir: &Path, entry: ToolManifestEntry) {
+    let tool = entry.tool.clone();
+    let libraries = entry.libraries.clone();
+    let replaced = record_install(app_handle, entry);
+    if is_app_bin_dir(app_handle, dir) {
+        if let Err(e) = archive_replaced(dir, &tool, replaced, &libraries) {
+            eprintln!(
+                "[tools] Warning: failed to back up previous {}: {}",
+                tool, e
+            );
+        }
     }
-    Ok(())
 }
 
-async fn download_deno(app_handle: &tauri::AppHandle, dest: &PathBuf) -> Result<(), String> {
-    let url =
-        "https://github.com/denoland/deno/releases/latest/download/deno-x86_64-pc-windows-msvc.zip";
-    let zip_path = dest.join("deno-update.zip");
-    download_tool_payload(app_handle, "deno", url, &zip_path).await?;
-    emit_progress(app_handle, "deno", 99.0, "Extracting deno.exe...");
-    let extracted = extract_from_zip(app_handle, "deno", &zip_path, dest, vec!["deno.exe"])?;
+/// Take the file locks for `spec` in `dest`, waiting (cancellably) for any other install
+/// of the same files to finish first.
+async fn lock_install_files(
+    app_handle: &tauri::AppHandle,
+    spec: &dyn ToolSpec,
+    dest: &Path,
+) -> Result<ToolFilesGuard, String> {
+    if let Ok(guard) = try_lock_tool_files(app_handle, dest, spec) {
+        return Ok(guard);
+    }
     emit_progress(
         app_handle,
-        "deno",
-        100.0,
-        &format!("Extracted: {}", extracted.join(", ")),
+        spec.id(),
+        0.0,
+        &format!("Waiting for another {} install to finish...", spec.id()),
     );
-    if let Err(e) = fs::remove_file(&zip_path) {
-        eprintln!("[tools] Warning: failed to clean up {:?}: {}", zip_path, e);
+    let token = transfer_token(app_handle, spec.id());
+    or_cancelled(token.as_ref(), lock_tool_files(app_handle, dest, spec)).await?
+}
+
+/// Install `tool` into `dest` from the first working source in the tool source registry
+/// and record it in the tool manifest. Without a `variant` the one recorded for `dest` is
+/// kept. An explicit `version` (release tag) is installed and pinned, `latest` clears the
+/// pin, and without one the pinned release is kept. With `preflight` the update checks
+/// run again once the file locks are held. Returns the verified SHA-256.
+async fn install_tool(
+    app_handle: &tauri::AppHandle,
+    spec: &dyn ToolSpec,
+    dest: &Path,
+    channel: Option<&str>,
+    variant: Option<&str>,
+    version: Option<&str>,
+    preflight: bool,
+) -> Result<String, String> {
+    let tool = spec.id();
+    let channel = spec.resolve_channel(channel);
+    let version = version.map(check_version_tag).transpose()?;
+    let pinned = match version {
+        Some(LATEST_VERSION) => None,
+        Some(version) => Some(version.to_string()),
+        None => pinned_version(app_handle, tool),
+    };
+    let _files = lock_install_files(app_handle, spec, dest).await?;
+    let recorded_variant = match variant {
+        Some(_) => None,
+        None => recorded_entry(app_handle, tool, dest).and_then(|entry| entry.variant),
+    };
+    let variant = variant.or(recorded_variant.as_deref());
+    if preflight {
+        // Another install may have changed the files between the caller's check and the lock.
+        let (handle, dir) = (app_handle.clone(), dest.to_path_buf());
+        let (spec, planned_variant) = (tool_spec(tool)?, variant.map(str::to_string));
+        let plan = tauri::async_runtime::spawn_blocking(move || {
+            plan_tool_update(&handle, spec, &dir, planned_variant.as_deref())
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        if let Some(failure) = plan.failure() {
+            return Err(format!(
+                "Cannot update {} in {}: {}",
+                tool,
+                dest.display(),
+                failure
+            ));
+        }
+    }
+    let plan = resolve_tool_install(app_handle, spec, channel, variant, pinned.as_deref()).await?;
+    let payload = if plan.asset.is_archive() {
+        let archive_path = dest.join(format!(
+            "{}-update.{}",
+            tool,
+            archive_extension(&plan.asset.asset)
+        ));
+        let targets = plan.asset.extract_targets();
+        install_from_archive(
+            app_handle,
+            tool,
+            &plan.sources,
+            &archive_path,
+            dest,
+            targets,
+        )
+        .await?
+    } else {
+        let dest_file = dest.join(plan.asset.installed_name());
+        let payload =
+            download_tool_payload_from_sources(app_handle, tool, &plan.sources, &dest_file).await?;
+        make_executable(&dest_file)?;
+        payload
+    };
+
+    let libraries = payload
+        .extracted
+        .into_iter()
+        .filter(|name| !spec.owns_binary(name))
+        .collect();
+    // Building the entry hashes and runs the new binary, and finishing moves the replaced
+    // files into a backup generation, so both stay off the async runtime.
+    let (handle, dir, id) = (app_handle.clone(), dest.to_path_buf(), tool.to_string());
+    let (url, sha256) = (payload.url, payload.sha256.clone());
+    let (channel, variant) = (channel.to_string(), plan.asset.variant);
+    tauri::async_runtime::spawn_blocking(move || {
+        let entry = ToolManifestEntry::new(&id, Some(&dir), InstallMethod::Download)
+            .with_source(&url, &sha256)
+            .with_channel(Some(&channel))
+            .with_variant(variant.as_deref())
+            .with_libraries(libraries);
+        finish_install(&handle, &dir, entry);
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if version.is_some() {
+        if let Err(e) = set_pin(app_handle, tool, pinned.as_deref()) {
+            eprintln!("[tools] Warning: failed to update {} pin: {}", tool, e);
+        }
     }
-    Ok(())
+    Ok(payload.sha256)
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -372,7 +500,13 @@ async fn download_deno(app_handle: &tauri::AppHandle, dest: &PathBuf) -> Result<
 pub struct ToolBatchItemResult {
     pub tool: String,
     pub success: bool,
+    /// Stopped through `cancel_tool_download`; never counted as a failure.
+    pub cancelled: bool,
+    /// Kind of archive limit the download tripped (`entry-too-large`, ...), if any.
+    pub rejection: Option<String>,
     pub message: String,
+    /// SHA-256 of the verified download (the archive for zip-packaged tools).
+    pub sha256: Option<String>,
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -383,38 +517,57 @@ pub struct ToolBatchResult {
     pub all_succeeded: bool,
 }
 
+/// Tool installs `download_tools` runs at once when the caller does not say.
+const DEFAULT_TOOL_INSTALL_CONCURRENCY: usize = 3;
+const MAX_TOOL_INSTALL_CONCURRENCY: usize = 8;
+
 async fn download_single_tool(
     app_handle: &tauri::AppHandle,
-    bin_dir: &PathBuf,
+    bin_dir: &Path,
     tool: &str,
     channels: &std::collections::HashMap<String, String>,
+    variants: &std::collections::HashMap<String, String>,
+    versions: &std::collections::HashMap<String, String>,
 ) -> Result<String, String> {
-    match tool {
-        "yt-dlp" => {
-            let is_nightly = channels.get("yt-dlp").map(|s| s.as_str()) == Some("nightly");
-            download_ytdlp(app_handle, bin_dir, is_nightly).await?;
-            Ok("Installed successfully".to_string())
-        }
-        "ffmpeg" => {
-            let is_nightly = channels.get("ffmpeg").map(|s| s.as_str()) == Some("nightly");
-            let variant = None;
-            download_ffmpeg(app_handle, bin_dir, variant, is_nightly).await?;
-            Ok("Installed successfully".to_string())
-        }
-        "aria2" => {
-            download_aria2(app_handle, bin_dir).await?;
-            Ok("Installed successfully".to_string())
-        }
-        "deno" => {
-            download_deno(app_handle, bin_dir).await?;
-            Ok("Installed successfully".to_string())
-        }
-        _ => Err(format!("Unknown tool: {}", tool)),
-    }
+    let channel = channels.get(tool).map(String::as_str);
+    let variant = variants
+        .get(tool)
+        .map(String::as_str)
+        .and_then(normalize_variant);
+    let version = versions.get(tool).map(String::as_str);
+    install_tool(
+        app_handle,
+        tool_spec(tool)?,
+        bin_dir,
+        channel,
+        variant,
+        version,
+        false,
+    )
+    .await
+}
+
+/// Download `tool` into the app bin dir again, e.g. after it failed an integrity check.
+pub async fn reinstall_managed_tool(
+    app_handle: &tauri::AppHandle,
+    tool: &str,
+    channel: Option<&str>,
+) -> Result<String, String> {
+    let spec = tool_spec(tool)?;
+    let paths = crate::app_paths::ensure_app_dirs(app_handle)?;
+    let bin_dir = PathBuf::from(&paths.bin_dir);
+    let _transfer = begin_transfer(app_handle, tool, true);
+    let sha256 = install_tool(app_handle, spec, &bin_dir, channel, None, None, false).await?;
+    Ok(format!("{} reinstalled (SHA-256 {})", tool, sha256))
 }
 
 /// Update a tool at its original (system) location instead of the app bin dir.
-/// FFmpeg installs essentials only. `channel` selects stable vs nightly for yt-dlp/ffmpeg.
+/// `variant` picks the FFmpeg build (essentials, full or shared); without one the variant
+/// recorded for that location is kept. `channel` selects stable vs nightly for yt-dlp/ffmpeg.
+/// `version` installs and pins a release tag (`latest` clears the pin).
+/// `background` applies the background bandwidth cap (unattended startup updates).
+/// Refuses to start when `tool_preflight::preflight_tool_update` would fail, and re-runs
+/// those checks once the file locks are held.
 #[tauri::command]
 pub async fn update_tool_at_path(
     app_handle: tauri::AppHandle,
@@ -422,31 +575,47 @@ pub async fn update_tool_at_path(
     dest_dir: String,
     variant: Option<String>,
     channel: Option<String>,
+    version: Option<String>,
+    background: Option<bool>,
 ) -> Result<String, String> {
+    let spec = tool_spec(&tool)?;
     let dest = PathBuf::from(&dest_dir);
     if !dest.exists() {
         return Err(format!("Directory does not exist: {}", dest_dir));
     }
 
-    let is_nightly = channel.as_deref().unwrap_or("stable") == "nightly";
-
-    match tool.as_str() {
-        "yt-dlp" => {
-            download_ytdlp(&app_handle, &dest, is_nightly).await?;
-        }
-        "ffmpeg" => {
-            download_ffmpeg(&app_handle, &dest, variant, is_nightly).await?;
-        }
-        "aria2" => {
-            download_aria2(&app_handle, &dest).await?;
-        }
-        "deno" => {
-            download_deno(&app_handle, &dest).await?;
-        }
-        _ => return Err(format!("Unknown tool: {}", tool)),
+    let variant = variant.as_deref().and_then(normalize_variant);
+    let plan = {
+        let (app_handle, dest) = (app_handle.clone(), dest.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            plan_tool_update(&app_handle, spec, &dest, variant)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    };
+    if let Some(failure) = plan.failure() {
+        return Err(format!(
+            "Cannot update {} in {}: {}",
+            tool, dest_dir, failure
+        ));
     }
 
-    Ok(format!("{} updated at {}", tool, dest_dir))
+    let _transfer = begin_transfer(&app_handle, &tool, background.unwrap_or(false));
+    let sha256 = install_tool(
+        &app_handle,
+        spec,
+        &dest,
+        channel.as_deref(),
+        variant,
+        version.as_deref(),
+        true,
+    )
+    .await?;
+
+    Ok(format!(
+        "{} updated at {} (SHA-256 {})",
+        tool, dest_dir, sha256
+    ))
 }
 
 #[tauri::command]
@@ -454,16 +623,14 @@ pub async fn download_tools(
     app_handle: tauri::AppHandle,
     tools: Vec<String>,
     channels: Option<std::collections::HashMap<String, String>>,
+    variants: Option<std::collections::HashMap<String, String>>,
+    versions: Option<std::collections::HashMap<String, String>>,
+    background: Option<bool>,
+    concurrency: Option<usize>,
 ) -> Result<ToolBatchResult, String> {
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = app_handle;
-        let _ = tools;
-        let _ = channels;
-        return Err("download_tools is currently supported on Windows only".to_string());
-    }
-
     let ch = channels.unwrap_or_default();
+    let variants = variants.unwrap_or_default();
+    let versions = versions.unwrap_or_default();
     let paths = crate::app_paths::ensure_app_dirs(&app_handle)?;
     let bin_dir = PathBuf::from(&paths.bin_dir);
 
@@ -472,39 +639,94 @@ pub async fn download_tools(
     }
 
     let mut seen = HashSet::new();
-    let mut results: Vec<ToolBatchItemResult> = Vec::new();
-
-    for tool in tools {
-        if !seen.insert(tool.clone()) {
-            continue;
-        }
-
-        match download_single_tool(&app_handle, &bin_dir, &tool, &ch).await {
-            Ok(message) => results.push(ToolBatchItemResult {
+    let queued: Vec<String> = tools
+        .into_iter()
+        .filter(|tool| seen.insert(tool.clone()))
+        .collect();
+    // Register every queued tool up front so it can be cancelled before its turn.
+    let transfers: Vec<_> = queued
+        .iter()
+        .map(|tool| begin_transfer(&app_handle, tool, background.unwrap_or(false)))
+        .collect();
+    let limit = concurrency
+        .unwrap_or(DEFAULT_TOOL_INSTALL_CONCURRENCY)
+        .clamp(1, MAX_TOOL_INSTALL_CONCURRENCY);
+
+    // Each install gets its own task so one tool's download or extraction never holds up
+    // another's; the semaphore keeps at most `limit` of them running.
+    let permits = Arc::new(Semaphore::new(limit));
+    let (ch, variants, versions) = (Arc::new(ch), Arc::new(variants), Arc::new(versions));
+    let installs: Vec<_> = queued
+        .into_iter()
+        .zip(transfers)
+        .map(|(tool, transfer)| {
+            let (app_handle, bin_dir, permits) =
+                (app_handle.clone(), bin_dir.clone(), permits.clone());
+            let (ch, variants, versions) = (ch.clone(), variants.clone(), versions.clone());
+            let task_tool = tool.clone();
+            let task = tauri::async_runtime::spawn(async move {
+                let _permit = permits.acquire_owned().await.map_err(|e| e.to_string())?;
+                if transfer.is_cancelled() {
+                    return Err(CANCELLED_ERROR.to_string());
+                }
+                download_single_tool(&app_handle, &bin_dir, &task_tool, &ch, &variants, &versions)
+                    .await
+            });
+            (tool, task)
+        })
+        .collect();
+
+    // Await in queue order so results line up with the request.
+    let mut results = Vec::with_capacity(installs.len());
+    for (tool, task) in installs {
+        let outcome = task
+            .await
+            .map_err(|e| format!("{} install task failed: {}", tool, e))
+            .and_then(|outcome| outcome);
+        results.push(match outcome {
+            Ok(sha256) => ToolBatchItemResult {
                 tool,
                 success: true,
-                message,
-            }),
-            Err(message) => results.push(ToolBatchItemResult {
+                cancelled: false,
+                rejection: None,
+                message: "Installed successfully (SHA-256 verified)".to_string(),
+                sha256: Some(sha256),
+            },
+            Err(message) => ToolBatchItemResult {
                 tool,
                 success: false,
+                cancelled: is_cancelled_error(&message),
+                rejection: rejection_kind(&message).map(str::to_string),
                 message,
-            }),
-        }
+                sha256: None,
+            },
+        });
     }
 
     let success_count = results.iter().filter(|item| item.success).count();
-    let failure_count = results.len().saturating_sub(success_count);
-    let all_succeeded = failure_count == 0;
+    let cancelled_count = results.iter().filter(|item| item.cancelled).count();
+    let failure_count = results
+        .len()
+        .saturating_sub(success_count + cancelled_count);
+    let all_succeeded = success_count == results.len();
 
     let summary = if results.is_empty() {
         "No tools were selected".to_string()
     } else if all_succeeded {
         format!("{} tool(s) completed successfully", success_count)
-    } else if success_count == 0 {
+    } else if success_count == 0 && cancelled_count == 0 {
         format!("All {} tool(s) failed", failure_count)
     } else {
-        format!("{} succeeded, {} failed", success_count, failure_count)
+        [
+            (success_count, "succeeded"),
+            (failure_count, "failed"),
+            (cancelled_count, "cancelled"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect::<Vec<_>>()
+        .join(", ")
     };
 
     Ok(ToolBatchResult {
@@ -541,23 +763,45 @@ pub fn stage_manual_tool(
         return Err("Source path is not a file".to_string());
     }
 
-    let (dest_name, expected_filename, extra_sidecar) = match tool.as_str() {
-        "yt-dlp" => ("yt-dlp.exe", "yt-dlp.exe", None),
-        "ffmpeg" => ("ffmpeg.exe", "ffmpeg.exe", Some("ffprobe.exe")),
-        "aria2" => ("aria2c.exe", "aria2c.exe", None),
-        "deno" => ("deno.exe", "deno.exe", None),
-        _ => return Err("Unsupported tool id".to_string()),
-    };
+    let spec = tool_spec(&tool).map_err(|_| "Unsupported tool id".to_string())?;
+    let dest_name = spec.primary_binary();
+    let _files = try_lock_tool_files(&app_handle, &bin_dir, spec)?;
 
     if let Some(file_name) = source_path.file_name().and_then(|n| n.to_str()) {
-        if !file_name.eq_ignore_ascii_case(expected_filename) {
+        if !file_name.eq_ignore_ascii_case(dest_name) {
             return Err(format!(
                 "Expected '{}' but got '{}'. Please select the correct binary.",
-                expected_filename, file_name
+                dest_name, file_name
             ));
         }
     }
 
+    // Sidecars (ffprobe next to ffmpeg) are staged too when they sit beside the source.
+    let sidecar_sources: Vec<(&str, PathBuf)> = source_path
+        .parent()
+        .map(|parent| {
+            spec.sidecars()
+                .iter()
+                .map(|&sidecar_name| (sidecar_name, parent.join(sidecar_name)))
+                .filter(|(_, path)| path.is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut needed = 0u64;
+    for path in std::iter::once(&source_path).chain(sidecar_sources.iter().map(|(_, path)| path)) {
+        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if size > MAX_ENTRY_SIZE {
+            return Err(ArchiveLimitError::EntryTooLarge {
+                name: path.to_string_lossy().to_string(),
+                size,
+                limit: MAX_ENTRY_SIZE,
+            }
+            .into());
+        }
+        needed += size;
+    }
+    ensure_disk_space(&bin_dir, needed)?;
+
     let dest_path = bin_dir.join(dest_name);
     let temp_dest = temp_path_for(&dest_path)?;
 
@@ -574,46 +818,53 @@ pub fn stage_manual_tool(
         return Err("Copied file is empty".to_string());
     }
 
-    safe_replace_with_backup(&dest_path, &temp_dest)?;
-
-    if let Some(sidecar_name) = extra_sidecar {
-        if let Some(parent) = source_path.parent() {
-            let sidecar_source = parent.join(sidecar_name);
-            if sidecar_source.exists() && sidecar_source.is_file() {
-                let sidecar_dest = bin_dir.join(sidecar_name);
-                fs::copy(&sidecar_source, &sidecar_dest).map_err(|e| {
-                    format!(
-                        "Staged {} but failed to copy sidecar {}: {}",
-                        dest_name, sidecar_name, e
-                    )
-                })?;
+    make_executable(&temp_dest)?;
+    let mut replacements = vec![Replacement {
+        dest: dest_path.clone(),
+        incoming: temp_dest.clone(),
+    }];
+
+    for (sidecar_name, sidecar_source) in &sidecar_sources {
+        let sidecar_dest = bin_dir.join(sidecar_name);
+        let sidecar_temp = temp_path_for(&sidecar_dest)?;
+        let copied = fs::copy(sidecar_source, &sidecar_temp)
+            .map_err(|e| e.to_string())
+            .and_then(|_| make_executable(&sidecar_temp));
+        if let Err(e) = copied {
+            let _ = fs::remove_file(&sidecar_temp);
+            for replacement in &replacements {
+                let _ = fs::remove_file(&replacement.incoming);
             }
+            return Err(format!(
+                "Failed to copy sidecar {} for {}: {}",
+                sidecar_name, dest_name, e
+            ));
+        }
+        replacements.push(Replacement {
+            dest: sidecar_dest,
+            incoming: sidecar_temp,
+        });
+    }
+
+    // The binary and its sidecars go in together, so they never end up from different builds.
+    let temps: Vec<PathBuf> = replacements.iter().map(|r| r.incoming.clone()).collect();
+    if let Err(e) = replace_all(&app_handle, &tool, replacements, true) {
+        for temp in &temps {
+            let _ = fs::remove_file(temp);
         }
+        return Err(e);
     }
 
+    let source_sha256 = sha256_of_path(&source_path)?;
+    let entry = ToolManifestEntry::new(&tool, Some(&bin_dir), InstallMethod::Manual)
+        .with_source(&source_path.to_string_lossy(), &source_sha256);
+    finish_install(&app_handle, &bin_dir, entry);
+
     Ok(dest_path.to_string_lossy().to_string())
 }
 
 // ── Tool backup / rollback ──
 
-pub const TOOL_BINARIES: &[(&str, &[&str])] = &[
-    ("yt-dlp", &["yt-dlp.exe"]),
-    ("ffmpeg", &["ffmpeg.exe", "ffprobe.exe"]),
-    ("aria2", &["aria2c.exe"]),
-    ("deno", &["deno.exe"]),
-];
-
-fn tool_id_for_binary(bin_name: &str) -> Option<&'static str> {
-    for &(id, binaries) in TOOL_BINARIES {
-        for &b in binaries {
-            if bin_name.eq_ignore_ascii_case(b) {
-                return Some(id);
-            }
-        }
-    }
-    None
-}
-
 fn collect_backup_dirs(
     app_handle: &tauri::AppHandle,
     extra_paths: &Option<Vec<String>>,
@@ -641,23 +892,46 @@ fn collect_backup_dirs(
     dirs
 }
 
+/// Modification time of `path` in milliseconds since the Unix epoch.
+fn modified_ms(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Versioned generations in the app bin dir, then single `.old` backups next to binaries.
 #[tauri::command]
 pub fn list_tool_backups(
     app_handle: tauri::AppHandle,
     extra_paths: Option<Vec<String>>,
-) -> Result<Vec<String>, String> {
-    let dirs = collect_backup_dirs(&app_handle, &extra_paths);
-    let mut tool_ids: Vec<String> = Vec::new();
+) -> Result<Vec<ToolBackup>, String> {
+    let mut backups = Vec::new();
+    if let Ok(paths) = crate::app_paths::resolve_paths(&app_handle) {
+        backups.extend(list_generation_backups(Path::new(&paths.bin_dir)));
+    }
 
+    let dirs = collect_backup_dirs(&app_handle, &extra_paths);
     for dir in &dirs {
         if let Ok(entries) = fs::read_dir(dir) {
             for entry in entries.flatten() {
                 let name = entry.file_name().to_string_lossy().to_string();
                 if name.ends_with(".old") {
                     let original = name.trim_end_matches(".old");
-                    if let Some(id) = tool_id_for_binary(original) {
-                        if !tool_ids.contains(&id.to_string()) {
-                            tool_ids.push(id.to_string());
+                    if let Some(id) = tool_for_binary(original).map(|spec| spec.id()) {
+                        let dir_label = dir.to_string_lossy().to_string();
+                        if !backups
+                            .iter()
+                            .any(|b| b.tool == id && b.version.is_none() && b.path == dir_label)
+                        {
+                            backups.push(ToolBackup {
+                                tool: id.to_string(),
+                                version: None,
+                                created_at: modified_ms(&entry.path()),
+                                path: dir_label,
+                            });
                         }
                     }
                 }
@@ -665,57 +939,64 @@ pub fn list_tool_backups(
         }
     }
 
-    Ok(tool_ids)
+    Ok(backups)
 }
 
+/// Restore a backup of `tool`: generation `version` from the app bin dir, or when no version
+/// is given the newest generation, falling back to `.old` files.
 #[tauri::command]
 pub fn rollback_tool(
     app_handle: tauri::AppHandle,
     tool: String,
     extra_paths: Option<Vec<String>>,
+    version: Option<String>,
 ) -> Result<String, String> {
-    let dirs = collect_backup_dirs(&app_handle, &extra_paths);
+    let spec = tool_spec(&tool)?;
+    let binaries = spec.binaries();
 
-    let binaries: &[&str] = TOOL_BINARIES
-        .iter()
-        .find(|&&(id, _)| id == tool.as_str())
-        .map(|&(_, bins)| bins)
-        .ok_or_else(|| format!("Unknown tool: {}", tool))?;
+    if let Ok(paths) = crate::app_paths::resolve_paths(&app_handle) {
+        let bin_dir = PathBuf::from(paths.bin_dir);
+        let _files = try_lock_tool_files(&app_handle, &bin_dir, spec)?;
+        if let Some(restored) =
+            restore_generation(&app_handle, &bin_dir, &tool, version.as_deref())?
+        {
+            record_rollback(&app_handle, &tool, &bin_dir, restored.entry);
+            return Ok(format!(
+                "Rolled back to {}: {} ({})",
+                restored.version,
+                restored.files.join(", "),
+                bin_dir.display()
+            ));
+        }
+    }
+    if let Some(version) = version {
+        return Err(format!("No {} backup for version {}", tool, version));
+    }
 
+    let dirs = collect_backup_dirs(&app_handle, &extra_paths);
     let mut rolled_back = Vec::new();
     // Prefer app-managed bin; only fall through to extra path dirs if needed.
     for dir in &dirs {
         let mut dir_hits = Vec::new();
+        let mut replacements = Vec::new();
         for &bin_name in binaries {
             let current = dir.join(bin_name);
-            let backup = dir.join(format!("{}.old", bin_name));
+            let backup = backup_path_for(&current)?;
 
             if !backup.exists() {
                 continue;
             }
 
-            let temp = dir.join(format!("{}.rollback-tmp", bin_name));
-            if current.exists() {
-                fs::rename(&current, &temp)
-                    .map_err(|e| format!("Failed to move current {} aside: {}", bin_name, e))?;
-            }
-
-            match fs::rename(&backup, &current) {
-                Ok(()) => {
-                    if temp.exists() {
-                        let _ = fs::remove_file(&temp);
-                    }
-                    dir_hits.push(format!("{} ({})", bin_name, dir.display()));
-                }
-                Err(e) => {
-                    if temp.exists() {
-                        let _ = fs::rename(&temp, &current);
-                    }
-                    return Err(format!("Failed to restore backup for {}: {}", bin_name, e));
-                }
-            }
+            replacements.push(Replacement {
+                dest: current,
+                incoming: backup,
+            });
+            dir_hits.push(format!("{} ({})", bin_name, dir.display()));
         }
         if !dir_hits.is_empty() {
+            let _files = try_lock_tool_files(&app_handle, dir, spec)?;
+            replace_all(&app_handle, &tool, replacements, false)?;
+            record_rollback(&app_handle, &tool, dir, None);
             rolled_back.extend(dir_hits);
             break;
         }
@@ -736,13 +1017,15 @@ pub fn cleanup_tool_backup(
 ) -> Result<String, String> {
     let dirs = collect_backup_dirs(&app_handle, &extra_paths);
 
-    let binaries: &[&str] = TOOL_BINARIES
-        .iter()
-        .find(|&&(id, _)| id == tool.as_str())
-        .map(|&(_, bins)| bins)
-        .ok_or_else(|| format!("Unknown tool: {}", tool))?;
+    let binaries = tool_spec(&tool)?.binaries();
 
     let mut cleaned = Vec::new();
+    if let Ok(paths) = crate::app_paths::resolve_paths(&app_handle) {
+        let removed = remove_generations(Path::new(&paths.bin_dir), &tool)?;
+        if removed > 0 {
+            cleaned.push(format!("{} versioned backup(s)", removed));
+        }
+    }
     for dir in &dirs {
         for &bin_name in binaries {
             let backup = dir.join(format!("{}.old", bin_name));
@@ -765,13 +1048,21 @@ pub fn cleanup_all_backups(
     let dirs = collect_backup_dirs(&app_handle, &extra_paths);
 
     let mut count = 0u32;
+    if let Ok(paths) = crate::app_paths::resolve_paths(&app_handle) {
+        for spec in tool_specs() {
+            match remove_generations(Path::new(&paths.bin_dir), spec.id()) {
+                Ok(removed) => count += removed as u32,
+                Err(e) => eprintln!("[tools] Warning: {}", e),
+            }
+        }
+    }
     for dir in &dirs {
         if let Ok(entries) = fs::read_dir(dir) {
             for entry in entries.flatten() {
                 let name = entry.file_name().to_string_lossy().to_string();
                 if name.ends_with(".old") {
                     let original = name.trim_end_matches(".old");
-                    if tool_id_for_binary(original).is_some() {
+                    if tool_for_binary(original).is_some() {
                         if let Err(e) = fs::remove_file(entry.path()) {
                             eprintln!("[tools] Warning: failed to remove {}: {}", name, e);
                         } else {
@@ -783,7 +1074,37 @@ pub fn cleanup_all_backups(
         }
     }
 
-    Ok(format!("Removed {} backup file(s)", count))
+    Ok(format!("Removed {} backup(s)", count))
+}
+
+/// Archives an install leaves in the bin dir when it is interrupted: `<tool>-update.<ext>`,
+/// and `<tool>.<ext>` from older builds, for every archive asset the registry lists here.
+fn leftover_archive_names(app_handle: &tauri::AppHandle) -> Vec<String> {
+    let Ok(registry) = load_tool_sources(app_handle) else {
+        return Vec::new();
+    };
+    let (os, arch) = (std::env::consts::OS, std::env::consts::ARCH);
+    let mut names = Vec::new();
+    for spec in tool_specs() {
+        let Ok(sources) = registry.tool(spec.source_id()) else {
+            continue;
+        };
+        for asset in &sources.platforms {
+            if asset.os != os || asset.arch != arch || !asset.is_archive() {
+                continue;
+            }
+            let extension = archive_extension(&asset.asset);
+            for name in [
+                format!("{}-update.{}", spec.id(), extension),
+                format!("{}.{}", spec.id(), extension),
+            ] {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+    }
+    names
 }
 
 #[tauri::command]
@@ -801,13 +1122,18 @@ pub fn cleanup_bin_tools(
     let mut removed = 0u32;
 
     for tool in tools {
-        let binaries: &[&str] = TOOL_BINARIES
+        let spec = tool_spec(&tool)?;
+        let binaries = spec.binaries();
+        let _files = try_lock_tool_files(&app_handle, &bin_dir, spec)?;
+        let libraries = recorded_entry(&app_handle, &tool, &bin_dir)
+            .map(|entry| entry.libraries)
+            .unwrap_or_default();
+
+        for bin_name in binaries
             .iter()
-            .find(|&&(id, _)| id == tool.as_str())
-            .map(|&(_, bins)| bins)
-            .ok_or_else(|| format!("Unknown tool: {}", tool))?;
-
-        for &bin_name in binaries {
+            .copied()
+            .chain(libraries.iter().map(String::as_str))
+        {
             let current = bin_dir.join(bin_name);
             if current.exists() {
                 fs::remove_file(&current)
@@ -822,16 +1148,11 @@ pub fn cleanup_bin_tools(
                 removed += 1;
             }
         }
+        removed += remove_generations(&bin_dir, &tool)? as u32;
+        forget_tool(&app_handle, &tool, &bin_dir);
     }
 
-    for extra in [
-        "aria2.zip",
-        "aria2-update.zip",
-        "deno.zip",
-        "deno-update.zip",
-        "ffmpeg-update.zip",
-        "ffmpeg-update.7z",
-    ] {
+    for extra in leftover_archive_names(&app_handle) {
         let p = bin_dir.join(extra);
         if p.exists() {
             let _ = fs::remove_file(&p);
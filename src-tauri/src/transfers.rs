@@ -0,0 +1,137 @@
+//! Cancellation handles for tool installs and app update downloads, keyed by tool id
+//! (`yt-dlp`, `ffmpeg`, ...) or job id (`app-update`).
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+/// Error string every cancelled transfer fails with; see `is_cancelled_error`.
+pub const CANCELLED_ERROR: &str = "Download cancelled";
+
+struct TransferEntry {
+    id: u64,
+    token: CancellationToken,
+    background: bool,
+}
+
+#[derive(Default)]
+pub struct TransferRegistry {
+    tokens: Mutex<HashMap<String, TransferEntry>>,
+    next_id: AtomicU64,
+}
+
+/// Registration for one running transfer; unregisters itself when dropped.
+pub struct TransferHandle {
+    app_handle: AppHandle,
+    key: String,
+    id: u64,
+    token: CancellationToken,
+}
+
+impl TransferHandle {
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+impl Drop for TransferHandle {
+    fn drop(&mut self) {
+        let registry = self.app_handle.state::<TransferRegistry>();
+        if let Ok(mut tokens) = registry.tokens.lock() {
+            // A newer transfer may have taken over the key; leave its token alone.
+            if tokens.get(&self.key).map(|entry| entry.id) == Some(self.id) {
+                tokens.remove(&self.key);
+            }
+        };
+    }
+}
+
+/// Register a cancellable transfer under `key`, replacing any stale registration.
+/// `background` transfers are throttled by the background bandwidth cap as well.
+pub fn begin_transfer(app_handle: &AppHandle, key: &str, background: bool) -> TransferHandle {
+    let registry = app_handle.state::<TransferRegistry>();
+    let id = registry.next_id.fetch_add(1, Ordering::Relaxed);
+    let token = CancellationToken::new();
+    if let Ok(mut tokens) = registry.tokens.lock() {
+        tokens.insert(
+            key.to_string(),
+            TransferEntry {
+                id,
+                token: token.clone(),
+                background,
+            },
+        );
+    }
+    TransferHandle {
+        app_handle: app_handle.clone(),
+        key: key.to_string(),
+        id,
+        token,
+    }
+}
+
+/// Token of the transfer currently registered under `key`, if any.
+pub fn transfer_token(app_handle: &AppHandle, key: &str) -> Option<CancellationToken> {
+    let registry = app_handle.state::<TransferRegistry>();
+    let tokens = registry.tokens.lock().ok()?;
+    tokens.get(key).map(|entry| entry.token.clone())
+}
+
+pub fn is_background_transfer(app_handle: &AppHandle, key: &str) -> bool {
+    let registry = app_handle.state::<TransferRegistry>();
+    let Ok(tokens) = registry.tokens.lock() else {
+        return false;
+    };
+    tokens
+        .get(key)
+        .map(|entry| entry.background)
+        .unwrap_or(false)
+}
+
+/// `Err(CANCELLED_ERROR)` once the transfer under `key` has been cancelled.
+pub fn check_cancelled(app_handle: &AppHandle, key: &str) -> Result<(), String> {
+    match transfer_token(app_handle, key) {
+        Some(token) if token.is_cancelled() => Err(CANCELLED_ERROR.to_string()),
+        _ => Ok(()),
+    }
+}
+
+pub fn is_cancelled_error(error: &str) -> bool {
+    error == CANCELLED_ERROR
+}
+
+/// Run `fut` unless `token` fires first, in which case it is dropped mid-flight.
+pub async fn or_cancelled<F: Future>(
+    token: Option<&CancellationToken>,
+    fut: F,
+) -> Result<F::Output, String> {
+    match token {
+        Some(token) => tokio::select! {
+            _ = token.cancelled() => Err(CANCELLED_ERROR.to_string()),
+            output = fut => Ok(output),
+        },
+        None => Ok(fut.await),
+    }
+}
+
+/// Cancel a running or queued tool install / update download.
+/// Returns `false` when nothing is registered under `id`.
+#[tauri::command]
+pub fn cancel_tool_download(app_handle: AppHandle, id: String) -> Result<bool, String> {
+    let registry = app_handle.state::<TransferRegistry>();
+    let tokens = registry
+        .tokens
+        .lock()
+        .map_err(|_| "Transfer registry lock poisoned".to_string())?;
+    match tokens.get(id.trim()) {
+        Some(entry) => {
+            entry.token.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
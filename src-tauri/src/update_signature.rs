@@ -0,0 +1,112 @@
+//! Detached minisign signature checks for app update installers. The public key is baked in
+//! at build time (`HALALDL_UPDATE_PUBKEY`), so a swapped installer plus a swapped checksum
+//! file on the download server is still rejected.
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use base64::Engine;
+use minisign_verify::{PublicKey, Signature};
+
+/// Minisign public key: the base64 key line, a full `minisign.pub`, or the base64-wrapped
+/// form the Tauri signer prints. Builds without it refuse every update.
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("HALALDL_UPDATE_PUBKEY");
+
+pub struct VerifiedSignature {
+    /// Minisign key id as printed by `minisign -V`, e.g. `8A2B3C4D5E6F7081`.
+    pub key_id: String,
+    pub trusted_comment: String,
+}
+
+/// Accept raw minisign text or the base64-wrapped form the Tauri signer writes.
+fn minisign_text(raw: &str) -> String {
+    let trimmed = raw.trim();
+    if trimmed.starts_with("untrusted comment:") {
+        return trimmed.to_string();
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(trimmed)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .filter(|text| text.trim_start().starts_with("untrusted comment:"))
+        .unwrap_or_else(|| trimmed.to_string())
+}
+
+/// The key line of a `minisign.pub`, or the input itself when it is already one line.
+fn public_key_line(text: &str) -> &str {
+    if text.starts_with("untrusted comment:") {
+        text.lines().nth(1).unwrap_or("").trim()
+    } else {
+        text.trim()
+    }
+}
+
+fn key_id_of(key_line: &str) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(key_line)
+        .ok()?;
+    let id: [u8; 8] = bytes.get(2..10)?.try_into().ok()?;
+    Some(format!("{:016X}", u64::from_le_bytes(id)))
+}
+
+fn builtin_public_key() -> Result<(PublicKey, String), String> {
+    let raw = UPDATE_PUBLIC_KEY
+        .filter(|key| !key.trim().is_empty())
+        .ok_or_else(|| {
+            "This build has no update signing key; refusing to install an unverifiable update"
+                .to_string()
+        })?;
+    let text = minisign_text(raw);
+    let key_line = public_key_line(&text);
+    let key = PublicKey::from_base64(key_line)
+        .map_err(|e| format!("Built-in update signing key is invalid: {}", e))?;
+    let key_id =
+        key_id_of(key_line).ok_or_else(|| "Built-in update signing key is invalid".to_string())?;
+    Ok((key, key_id))
+}
+
+/// Key id of the built-in release key, or an error when this build has none.
+pub fn update_signing_key_id() -> Result<String, String> {
+    builtin_public_key().map(|(_, key_id)| key_id)
+}
+
+/// Verify `signature` (minisign `.sig` contents) over the exact bytes of `path`.
+pub fn verify_update_signature(path: &Path, signature: &str) -> Result<VerifiedSignature, String> {
+    let (key, key_id) = builtin_public_key()?;
+    let signature = Signature::decode(&minisign_text(signature))
+        .map_err(|e| format!("Invalid update signature: {}", e))?;
+
+    let result = match key.verify_stream(&signature) {
+        Ok(mut verifier) => {
+            let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+            let mut buffer = vec![0u8; 64 * 1024];
+            loop {
+                let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+                if read == 0 {
+                    break;
+                }
+                verifier.update(&buffer[..read]);
+            }
+            verifier.finalize()
+        }
+        // Signatures from minisign < 0.8 sign the raw bytes instead of a BLAKE2b hash.
+        Err(minisign_verify::Error::UnsupportedLegacyMode) => {
+            let bytes = fs::read(path).map_err(|e| e.to_string())?;
+            key.verify(&bytes, &signature, true)
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => Ok(VerifiedSignature {
+            key_id,
+            trusted_comment: signature.trusted_comment().to_string(),
+        }),
+        Err(minisign_verify::Error::UnexpectedKeyId) => Err(format!(
+            "Update was not signed by the HalalDL release key ({})",
+            key_id
+        )),
+        Err(e) => Err(format!("Update signature verification failed: {}", e)),
+    }
+}
@@ -1,20 +1,35 @@
-fn http_client(app_handle: &tauri::AppHandle) -> Result<reqwest::Client, String> {
-    reqwest::Client::builder()
-        .user_agent(format!("HalalDL/{}", app_handle.package_info().version))
-        .connect_timeout(std::time::Duration::from_secs(10))
-        .timeout(std::time::Duration::from_secs(20))
-        .build()
-        .map_err(|e| e.to_string())
+use crate::http_client::{shared_client, API_REQUEST_TIMEOUT};
+use crate::tool_pins::pinned_version;
+use crate::tool_sources::{normalize_variant, tool_release_repo};
+use crate::tool_specs::tool_spec;
+
+/// Releases `list_available_tool_versions` returns when the caller does not say.
+const DEFAULT_RELEASE_LIST_LIMIT: usize = 20;
+/// Most GitHub returns on one page.
+const MAX_RELEASE_LIST_LIMIT: usize = 100;
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolRelease {
+    /// Tag to pass as the version to `download_tools` / `update_tool_at_path`.
+    pub tag: String,
+    pub name: Option<String>,
+    /// RFC 3339 publish time.
+    pub published_at: Option<String>,
+    pub prerelease: bool,
+    /// The tool is currently pinned to this release.
+    pub pinned: bool,
 }
 
 async fn github_latest_tag(app_handle: &tauri::AppHandle, repo: &str) -> Result<String, String> {
-    let client = http_client(app_handle)?;
+    let client = shared_client(app_handle)?;
     let res = client
         .get(format!(
             "https://api.github.com/repos/{}/releases/latest",
             repo
         ))
         .header("Accept", "application/vnd.github+json")
+        .timeout(API_REQUEST_TIMEOUT)
         .send()
         .await
         .map_err(|e| e.to_string())?;
@@ -38,10 +53,11 @@ async fn github_latest_tag(app_handle: &tauri::AppHandle, repo: &str) -> Result<
 }
 
 async fn fetch_plain_version(app_handle: &tauri::AppHandle, url: &str) -> Result<String, String> {
-    let client = http_client(app_handle)?;
+    let client = shared_client(app_handle)?;
     let res = client
         .get(url)
         .header("Accept", "text/plain,*/*")
+        .timeout(API_REQUEST_TIMEOUT)
         .send()
         .await
         .map_err(|e| e.to_string())?;
@@ -69,13 +85,19 @@ pub async fn fetch_latest_ytdlp_version(
     } else {
         "yt-dlp/yt-dlp"
     };
-    let tag = github_latest_tag(&app_handle, repo).await?;
+    let tag = match pinned_version(&app_handle, "yt-dlp") {
+        Some(tag) => tag,
+        None => github_latest_tag(&app_handle, repo).await?,
+    };
     Ok(tag.trim_start_matches('v').trim().to_string())
 }
 
 #[tauri::command]
 pub async fn fetch_latest_aria2_version(app_handle: tauri::AppHandle) -> Result<String, String> {
-    let tag = github_latest_tag(&app_handle, "aria2/aria2").await?;
+    let tag = match pinned_version(&app_handle, "aria2") {
+        Some(tag) => tag,
+        None => github_latest_tag(&app_handle, "aria2/aria2").await?,
+    };
     Ok(tag
         .trim_start_matches("release-")
         .trim_start_matches('v')
@@ -85,7 +107,10 @@ pub async fn fetch_latest_aria2_version(app_handle: tauri::AppHandle) -> Result<
 
 #[tauri::command]
 pub async fn fetch_latest_deno_version(app_handle: tauri::AppHandle) -> Result<String, String> {
-    let version = fetch_plain_version(&app_handle, "https://dl.deno.land/release-latest.txt").await?;
+    let version = match pinned_version(&app_handle, "deno") {
+        Some(tag) => tag,
+        None => fetch_plain_version(&app_handle, "https://dl.deno.land/release-latest.txt").await?,
+    };
     Ok(version.trim_start_matches('v').trim().to_string())
 }
 
@@ -94,6 +119,9 @@ pub async fn fetch_latest_ffmpeg_version(
     app_handle: tauri::AppHandle,
     channel: Option<String>,
 ) -> Result<String, String> {
+    if let Some(tag) = pinned_version(&app_handle, "ffmpeg") {
+        return Ok(tag);
+    }
     let is_nightly = channel.as_deref().unwrap_or("stable") == "nightly";
     let url = if is_nightly {
         "https://www.gyan.dev/ffmpeg/builds/git-version"
@@ -102,3 +130,85 @@ pub async fn fetch_latest_ffmpeg_version(
     };
     fetch_plain_version(&app_handle, url).await
 }
+
+/// Latest (or pinned) version of `tool` on `channel`, from the matching `fetch_latest_*`.
+pub async fn latest_tool_version(
+    app_handle: &tauri::AppHandle,
+    tool: &str,
+    channel: &str,
+) -> Result<String, String> {
+    let app_handle = app_handle.clone();
+    let channel = Some(channel.to_string());
+    match tool {
+        "yt-dlp" => fetch_latest_ytdlp_version(app_handle, channel).await,
+        "ffmpeg" => fetch_latest_ffmpeg_version(app_handle, channel).await,
+        "aria2" => fetch_latest_aria2_version(app_handle).await,
+        "deno" => fetch_latest_deno_version(app_handle).await,
+        _ => Err(format!("No latest-version check for {}", tool)),
+    }
+}
+
+/// Recent GitHub releases of `tool` on `channel` (newest first), to choose a version to pin.
+#[tauri::command]
+pub async fn list_available_tool_versions(
+    app_handle: tauri::AppHandle,
+    tool: String,
+    channel: Option<String>,
+    variant: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ToolRelease>, String> {
+    let spec = tool_spec(&tool)?;
+    let channel = spec.resolve_channel(channel.as_deref());
+    let variant = variant.as_deref().and_then(normalize_variant);
+    let repo = tool_release_repo(&app_handle, spec, channel, variant)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_RELEASE_LIST_LIMIT)
+        .clamp(1, MAX_RELEASE_LIST_LIMIT);
+
+    let client = shared_client(&app_handle)?;
+    let res = client
+        .get(format!(
+            "https://api.github.com/repos/{}/releases?per_page={}",
+            repo, limit
+        ))
+        .header("Accept", "application/vnd.github+json")
+        .timeout(API_REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !res.status().is_success() {
+        return Err(format!("HTTP {} ({})", res.status(), repo));
+    }
+
+    let json: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
+    let pinned = pinned_version(&app_handle, spec.id());
+    let text = |release: &serde_json::Value, key: &str| {
+        release
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    Ok(json
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|release| release.get("draft").and_then(|v| v.as_bool()) != Some(true))
+        .filter_map(|release| {
+            let tag = text(release, "tag_name")?;
+            Some(ToolRelease {
+                pinned: pinned.as_deref() == Some(tag.as_str()),
+                name: text(release, "name"),
+                published_at: text(release, "published_at"),
+                prerelease: release
+                    .get("prerelease")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                tag,
+            })
+        })
+        .collect())
+}
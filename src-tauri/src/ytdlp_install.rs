@@ -0,0 +1,343 @@
+//! How a system yt-dlp was installed, so it is upgraded with the tool that owns it: pipx,
+//! `uv tool`, a virtualenv, conda, Homebrew or plain pip. Distro packages and installs that
+//! cannot be identified are reported instead of handing them to whichever pip is on PATH.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::tool_manifest::InstallMethod;
+
+const PACKAGE: &str = "yt-dlp";
+/// Console scripts pip writes are a few hundred bytes.
+const MAX_SCRIPT_SIZE: u64 = 64 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum YtDlpInstallMethod {
+    Pipx,
+    UvTool,
+    Venv,
+    Conda,
+    Homebrew,
+    /// Installed by the OS package manager (apt, dnf, pacman, snap, ...).
+    Distro,
+    /// pip into a regular Python installation.
+    Pip,
+    Unknown,
+}
+
+impl YtDlpInstallMethod {
+    pub fn manifest_method(self) -> InstallMethod {
+        match self {
+            Self::Venv | Self::Pip => InstallMethod::Pip,
+            _ => InstallMethod::PackageManager,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YtDlpInstall {
+    pub path: String,
+    pub method: YtDlpInstallMethod,
+    /// Program and arguments that upgrade this install; `None` when HalalDL should not.
+    pub upgrade_command: Option<Vec<String>>,
+    /// Why `upgrade_command` is missing.
+    pub reason: Option<String>,
+}
+
+fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(target_os = "windows") {
+        "USERPROFILE"
+    } else {
+        "HOME"
+    };
+    std::env::var_os(var).map(PathBuf::from)
+}
+
+fn env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Directories pipx may keep its venvs in (`PIPX_HOME`, then the defaults of old and new pipx).
+fn pipx_venv_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = env_dir("PIPX_HOME").into_iter().collect();
+    if let Some(home) = home_dir() {
+        roots.push(home.join(".local").join("pipx"));
+        roots.push(home.join(".local").join("share").join("pipx"));
+        roots.push(home.join("pipx"));
+    }
+    if let Some(local) = env_dir("LOCALAPPDATA") {
+        roots.push(local.join("pipx").join("pipx"));
+    }
+    roots.into_iter().map(|root| root.join("venvs")).collect()
+}
+
+/// Directories `uv tool` may keep its environments in.
+fn uv_tool_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = env_dir("UV_TOOL_DIR").into_iter().collect();
+    if let Some(data) = env_dir("XDG_DATA_HOME") {
+        roots.push(data.join("uv").join("tools"));
+    }
+    if let Some(home) = home_dir() {
+        roots.push(home.join(".local").join("share").join("uv").join("tools"));
+    }
+    if let Some(app_data) = env_dir("APPDATA") {
+        roots.push(app_data.join("uv").join("tools"));
+    }
+    roots
+}
+
+fn has_ytdlp_env(roots: &[PathBuf]) -> bool {
+    roots.iter().any(|root| root.join(PACKAGE).is_dir())
+}
+
+/// Lowercase, `/`-separated form of `path` for substring checks.
+fn normalized(path: &Path) -> String {
+    path.to_string_lossy()
+        .to_ascii_lowercase()
+        .replace('\\', "/")
+}
+
+/// Python interpreter of the environment whose scripts live in `bin_dir`.
+fn env_python(bin_dir: &Path) -> Option<PathBuf> {
+    let candidates = if cfg!(target_os = "windows") {
+        vec![
+            bin_dir.join("python.exe"),
+            bin_dir.parent()?.join("python.exe"),
+        ]
+    } else {
+        vec![bin_dir.join("python3"), bin_dir.join("python")]
+    };
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+/// Interpreter named in the `#!` line of a pip-generated script. The standalone release is
+/// a zipapp with a shebang too, so only small scripts count.
+fn shebang_python(script: &Path) -> Option<PathBuf> {
+    if fs::metadata(script).ok()?.len() > MAX_SCRIPT_SIZE {
+        return None;
+    }
+    let head = fs::read(script).ok()?;
+    let text = std::str::from_utf8(&head).ok()?;
+    if !text.contains("yt_dlp") {
+        return None;
+    }
+    let line = text.lines().next()?.strip_prefix("#!")?.trim();
+    let mut parts = line.split_whitespace();
+    let program = parts.next()?;
+    let python = if program.ends_with("/env") {
+        parts.find(|part| !part.starts_with('-'))?
+    } else {
+        program
+    };
+    Path::new(python)
+        .file_name()?
+        .to_str()?
+        .starts_with("python")
+        .then(|| PathBuf::from(python))
+}
+
+fn conda_exe(prefix: &Path) -> PathBuf {
+    if let Some(conda) = env_dir("CONDA_EXE") {
+        return conda;
+    }
+    // Named environments live in `<base>/envs/<name>`; conda itself sits in the base.
+    let base = match prefix.parent() {
+        Some(envs) if envs.file_name().is_some_and(|name| name == "envs") => {
+            envs.parent().unwrap_or(prefix)
+        }
+        _ => prefix,
+    };
+    [
+        base.join("condabin").join("conda"),
+        base.join("bin").join("conda"),
+        base.join("Scripts").join("conda.exe"),
+        base.join("condabin").join("conda.bat"),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+    .unwrap_or_else(|| PathBuf::from("conda"))
+}
+
+fn conda_owns_ytdlp(prefix: &Path) -> bool {
+    fs::read_dir(prefix.join("conda-meta"))
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                let name = entry.file_name().to_string_lossy().to_ascii_lowercase();
+                name.starts_with("yt-dlp-") && name.ends_with(".json")
+            })
+        })
+        .unwrap_or(false)
+}
+
+fn pip_upgrade(python: &Path) -> Vec<String> {
+    vec![
+        python.to_string_lossy().to_string(),
+        "-m".to_string(),
+        "pip".to_string(),
+        "install".to_string(),
+        "--upgrade".to_string(),
+        PACKAGE.to_string(),
+    ]
+}
+
+fn command(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+/// Work out which tool installed the yt-dlp at `path` and how to upgrade it.
+pub fn detect_ytdlp_install(path: &Path) -> YtDlpInstall {
+    let (method, upgrade_command, reason) = classify(path);
+    YtDlpInstall {
+        path: path.to_string_lossy().to_string(),
+        method,
+        upgrade_command,
+        reason,
+    }
+}
+
+fn classify(path: &Path) -> (YtDlpInstallMethod, Option<Vec<String>>, Option<String>) {
+    use YtDlpInstallMethod::*;
+
+    // pipx, uv and Homebrew put symlinks on PATH; the target shows who owns it.
+    let real = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let real_lower = normalized(&real);
+    let bin_dir = real.parent().unwrap_or(Path::new(""));
+    let prefix = bin_dir.parent().unwrap_or(Path::new(""));
+
+    if real_lower.contains("/pipx/venvs/yt-dlp/") {
+        return (Pipx, Some(command(&["pipx", "upgrade", PACKAGE])), None);
+    }
+    if real_lower.contains("/uv/tools/yt-dlp/") {
+        return (
+            UvTool,
+            Some(command(&["uv", "tool", "upgrade", PACKAGE])),
+            None,
+        );
+    }
+    if let Some(index) = real_lower.find("/cellar/yt-dlp/") {
+        let brew = PathBuf::from(&real.to_string_lossy()[..index])
+            .join("bin")
+            .join("brew");
+        let brew = if brew.is_file() {
+            brew.to_string_lossy().to_string()
+        } else {
+            "brew".to_string()
+        };
+        return (
+            Homebrew,
+            Some(vec![brew, "upgrade".to_string(), PACKAGE.to_string()]),
+            None,
+        );
+    }
+
+    // On Windows pipx and uv copy launchers into `~/.local/bin` instead of linking them.
+    if normalized(bin_dir).ends_with("/.local/bin") {
+        match (
+            has_ytdlp_env(&pipx_venv_roots()),
+            has_ytdlp_env(&uv_tool_roots()),
+        ) {
+            (true, false) => return (Pipx, Some(command(&["pipx", "upgrade", PACKAGE])), None),
+            (false, true) => {
+                return (UvTool, Some(command(&["uv", "tool", "upgrade", PACKAGE])), None)
+            }
+            (true, true) => {
+                return (
+                    Unknown,
+                    None,
+                    Some(format!(
+                        "Both pipx and uv tool have yt-dlp installed; upgrade {} with the one that put it there",
+                        path.display()
+                    )),
+                )
+            }
+            (false, false) => {}
+        }
+    }
+
+    if prefix.join("conda-meta").is_dir() {
+        if conda_owns_ytdlp(prefix) {
+            let conda = conda_exe(prefix).to_string_lossy().to_string();
+            let mut args = vec![conda];
+            args.extend(command(&["update", "-y", "-p"]));
+            args.push(prefix.to_string_lossy().to_string());
+            args.push(PACKAGE.to_string());
+            return (Conda, Some(args), None);
+        }
+        // pip-installed into a conda environment: upgrade with that environment's pip.
+        if let Some(python) = env_python(bin_dir) {
+            return (Conda, Some(pip_upgrade(&python)), None);
+        }
+    }
+
+    if prefix.join("pyvenv.cfg").is_file() {
+        return match env_python(bin_dir) {
+            Some(python) => (Venv, Some(pip_upgrade(&python)), None),
+            None => (
+                Venv,
+                None,
+                Some(format!(
+                    "The virtualenv at {} has no Python interpreter",
+                    prefix.display()
+                )),
+            ),
+        };
+    }
+
+    if cfg!(not(target_os = "windows")) {
+        let distro_dirs = ["/usr/bin/", "/usr/lib/", "/usr/share/", "/bin/", "/snap/"];
+        if distro_dirs.iter().any(|dir| real_lower.starts_with(dir)) {
+            return (
+                Distro,
+                None,
+                Some(format!(
+                    "{} belongs to the system package manager; update yt-dlp with it (apt, dnf, pacman, snap, ...)",
+                    path.display()
+                )),
+            );
+        }
+    }
+
+    let python = if cfg!(target_os = "windows") {
+        bin_dir
+            .file_name()
+            .filter(|name| name.eq_ignore_ascii_case("scripts"))
+            .and_then(|_| env_python(bin_dir))
+    } else {
+        shebang_python(&real)
+    };
+    match python {
+        Some(python) => (Pip, Some(pip_upgrade(&python)), None),
+        None => (
+            Unknown,
+            None,
+            Some(format!(
+                "Could not tell how {} was installed (not pip, pipx, uv, conda or Homebrew); if it is the standalone release, let HalalDL manage yt-dlp instead",
+                path.display()
+            )),
+        ),
+    }
+}
+
+/// Turn a failed upgrade into advice when pip refused an externally managed Python.
+pub fn explain_upgrade_error(install: &YtDlpInstall, error: String) -> String {
+    if error.contains("externally-managed-environment") {
+        return format!(
+            "{} runs from an externally managed Python, which pip will not modify; reinstall yt-dlp with pipx or uv tool, or update it with the system package manager",
+            install.path
+        );
+    }
+    error
+}
+
+/// How the yt-dlp at `system_path` (or first on PATH) was installed and how it would be upgraded.
+#[tauri::command]
+pub fn detect_ytdlp_install_method(system_path: Option<String>) -> Result<YtDlpInstall, String> {
+    let path = crate::tools::system_ytdlp_path(system_path.as_deref())?;
+    Ok(detect_ytdlp_install(&path))
+}
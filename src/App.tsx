@@ -28,15 +28,19 @@ import {
   fetchLatestDenoVersion,
   fetchLatestFfmpegVersion,
   fetchLatestYtDlpVersion,
+  getToolIntegrityReport,
   hideMainWindowToTray,
   isUpdateAvailable,
   readTextFromClipboard,
   restoreMainWindow,
   showQuickDownloadWindow,
+  syncBandwidthLimits,
+  syncNetworkSettings,
   syncRuntimeSettings,
   takePendingLaunchUrls,
   updateTrayState,
   wasLaunchedFromAutostart,
+  type IntegrityReport,
 } from "@/lib/commands";
 import { checkAndStoreAppUpdate } from "@/lib/app-updates/service";
 import {
@@ -236,6 +240,36 @@ export default function App() {
     settings.trayMenuShowHideItem,
   ]);
 
+  useEffect(() => {
+    void syncNetworkSettings({
+      proxyUrl: settings.proxyUrl || null,
+      proxyUsername: settings.proxyUsername || null,
+      connectTimeoutSecs: settings.connectTimeoutSecs || null,
+      readTimeoutSecs: settings.readTimeoutSecs || null,
+      bindAddress: settings.bindAddress || null,
+      caCertificatesPath: settings.caCertificatesPath || null,
+    }).catch((error) => {
+      // The backend keeps the previous settings when the new ones are invalid.
+      toast.error(`Network settings not applied: ${String(error)}`, { id: "network-settings" });
+    });
+  }, [
+    settings.bindAddress,
+    settings.caCertificatesPath,
+    settings.connectTimeoutSecs,
+    settings.proxyUrl,
+    settings.proxyUsername,
+    settings.readTimeoutSecs,
+  ]);
+
+  useEffect(() => {
+    void syncBandwidthLimits({
+      maxBytesPerSecond: settings.toolDownloadMaxSpeed * 1024,
+      backgroundMaxBytesPerSecond: settings.backgroundToolDownloadMaxSpeed * 1024,
+    }).catch(() => {
+      void 0;
+    });
+  }, [settings.backgroundToolDownloadMaxSpeed, settings.toolDownloadMaxSpeed]);
+
   useEffect(() => {
     const initial = window.setTimeout(() => { void checkDueWatchlists(); }, 20_000);
     const interval = window.setInterval(() => { void checkDueWatchlists(); }, 60_000);
@@ -521,6 +555,45 @@ export default function App() {
     };
   }, [addClipboardDownload, processLaunchUrls]);
 
+  useEffect(() => {
+    if (!isTauriRuntime()) return;
+    let disposed = false;
+    let disposeIntegrity: (() => void) | undefined;
+
+    const announce = (report: IntegrityReport | null) => {
+      if (!report) return;
+      if (report.recovered.length > 0) {
+        toast.info(report.recovered.join("\n"), { id: "tool-recovery" });
+      }
+      if (report.allOk) return;
+      const damaged = report.binaries
+        .filter((binary) => binary.status !== "ok")
+        .map((binary) => `${binary.name} (${binary.status})`);
+      toast.warning(`Managed tools need attention: ${damaged.join(", ")}`, {
+        id: "tool-integrity",
+        description: "Restore or reinstall them from the Tools screen.",
+      });
+    };
+
+    void listen<IntegrityReport>("tool-integrity", (event) => {
+      announce(event.payload);
+    }).then((unlisten) => {
+      if (disposed) unlisten();
+      else disposeIntegrity = unlisten;
+    });
+    // The startup scan can finish before the listener is attached.
+    void getToolIntegrityReport()
+      .then(announce)
+      .catch(() => {
+        void 0;
+      });
+
+    return () => {
+      disposed = true;
+      if (disposeIntegrity) disposeIntegrity();
+    };
+  }, []);
+
   useEffect(() => {
     if (!isTauriRuntime()) return;
     if (!settings.enableBackgroundUpdateChecks) return;
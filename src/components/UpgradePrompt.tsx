@@ -244,7 +244,7 @@ export function UpgradePrompt() {
   );
 
   const handleUpgrade = useCallback(
-    async (overrideTools?: string[]) => {
+    async (overrideTools?: string[], background = false) => {
       const toolsToInstall = Array.from(new Set((overrideTools ?? actionToolIds).filter(Boolean)));
       if (toolsToInstall.length === 0) {
         setDismissedMissingKey(missingKey);
@@ -274,7 +274,7 @@ export function UpgradePrompt() {
         }
         setCurrentStatus("Downloading selected versions...");
 
-        const result = await downloadTools(toolsToInstall);
+        const result = await downloadTools(toolsToInstall, undefined, background);
         setBatchResult(result);
 
         const succeeded = getSuccessfulToolResults(result);
@@ -370,7 +370,8 @@ export function UpgradePrompt() {
       autoInstallStartedRef.current = true;
       void setFullSwitchAutoInstall(false);
       timer = window.setTimeout(() => {
-        void handleUpgrade(missingIds);
+        // Nobody asked for this install, so it runs under the background bandwidth cap.
+        void handleUpgrade(missingIds, true);
       }, 0);
     }).catch(() => {
       void 0;
@@ -291,7 +291,7 @@ export function usePersistenceInit(): MutableRefObject<boolean> {
               }
 
               try {
-                const backupIds = await listToolBackups();
+                const backupIds = [...new Set((await listToolBackups()).map((backup) => backup.tool))];
                 const currentTools = useToolsStore.getState().tools;
                 for (const t of currentTools) {
                   updateTool(t.id, { hasBackup: backupIds.includes(t.id) });
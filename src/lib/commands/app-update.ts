@@ -130,9 +130,18 @@ export async function resolveLatestAppUpdate(
   };
 }
 
+export interface VerifiedAppUpdate {
+  path: string;
+  sha256: string;
+  /** Minisign key id of the release key that signed the installer. */
+  keyId: string;
+  trustedComment: string;
+}
+
+/** Downloads the installer and checks its SHA-256 and `<asset>.sig` minisign signature. */
 export async function downloadAndVerifyAppUpdate(
   update: Pick<ResolvedAppUpdate, "downloadUrl" | "assetName" | "checksumUrl">
-): Promise<string> {
+): Promise<VerifiedAppUpdate> {
   if (!update.downloadUrl || !update.assetName || !update.checksumUrl) {
     throw new Error(
       "Verified app update download is unavailable for this release."
@@ -141,7 +150,7 @@ export async function downloadAndVerifyAppUpdate(
 
   const dest = await join(await downloadDir(), update.assetName);
 
-  return invoke<string>("download_and_verify_app_update", {
+  return invoke<VerifiedAppUpdate>("download_and_verify_app_update", {
     url: update.downloadUrl,
     dest,
     checksumUrl: update.checksumUrl,
@@ -11,6 +11,7 @@ export {
   type GitHubRelease,
   type GitHubReleaseAsset,
   type ResolvedAppUpdate,
+  type VerifiedAppUpdate,
 } from "./app-update";
 export {
   checkYtDlpVersion,
@@ -18,6 +19,7 @@ export {
   checkAria2Version,
   checkDenoVersion,
   upgradeYtDlpViaPip,
+  detectYtDlpInstall,
   resolveSystemToolPath,
   resolveSystemToolPaths,
   isYtDlpPipPath,
@@ -27,11 +29,25 @@ export {
   fetchLatestDenoVersion,
   fetchLatestFfmpegVersion,
   type ToolCheckResult,
+  type YtDlpInstall,
+  type YtDlpInstallMethod,
 } from "./tool-checks";
 export {
   updateToolAtPath,
+  preflightToolUpdate,
   downloadTools,
+  cancelToolDownload,
   stageManualTool,
+  exportToolBundle,
+  importToolBundle,
+  getToolManifest,
+  listAvailableToolVersions,
+  getToolPins,
+  setToolPin,
+  probeToolVersion,
+  checkToolVersion,
+  scanToolIntegrity,
+  getToolIntegrityReport,
   pickFile,
   revealToolInExplorer,
   listToolBackups,
@@ -39,8 +55,29 @@ export {
   cleanupToolBackup,
   cleanupAllBackups,
   downloadUrlToFile,
+  downloadUrlSegmented,
   postFormForText,
   clearYtDlpCache,
+  type PreflightCheckKind,
+  type PreflightCheck,
+  type PlannedFile,
+  type ToolUpdatePlan,
+  type ToolBundleFile,
+  type ToolBundleEntry,
+  type ToolBundleResult,
+  type ToolManifestFile,
+  type ToolManifestEntry,
+  type ToolManifest,
+  type ToolRelease,
+  type ToolPin,
+  type ToolVersion,
+  type ToolVersionStatus,
+  type ToolBackup,
+  type BinaryStatus,
+  type IntegrityRepair,
+  type BinaryIntegrity,
+  type ToolRepairResult,
+  type IntegrityReport,
 } from "./tool-commands";
 export {
   revealInExplorer,
@@ -55,6 +92,12 @@ export {
 } from "./file-commands";
 export {
   syncRuntimeSettings,
+  syncBandwidthLimits,
+  syncNetworkSettings,
+  saveProxyPassword,
+  hasProxyPassword,
+  removeProxyPassword,
+  inspectTlsCertificateChain,
   updateTrayState,
   restoreMainWindow,
   showQuickDownloadWindow,
@@ -66,6 +109,10 @@ export {
   takePendingLaunchUrls,
   sendNativeWindowsToast,
   type RuntimeSettingsSyncPayload,
+  type BandwidthLimitsPayload,
+  type NetworkSettingsPayload,
+  type CertificateInfo,
+  type TlsChainReport,
   type TrayStatePayload,
   type NativeWindowsToastPayload,
 } from "./runtime-commands";
@@ -15,6 +15,49 @@ export interface TrayStatePayload {
   toolUpdateCount: number;
 }
 
+/** Bytes per second; omit or 0 for unlimited. */
+export interface BandwidthLimitsPayload {
+  maxBytesPerSecond?: number | null;
+  backgroundMaxBytesPerSecond?: number | null;
+}
+
+/** Proxy, timeouts, bind address and extra CAs for backend requests and spawned tools. */
+export interface NetworkSettingsPayload {
+  proxyUrl?: string | null;
+  proxyUsername?: string | null;
+  /** Seconds; omit or 0 for the default. */
+  connectTimeoutSecs?: number | null;
+  readTimeoutSecs?: number | null;
+  bindAddress?: string | null;
+  caCertificatesPath?: string | null;
+}
+
+export interface CertificateInfo {
+  subject: string;
+  issuer: string;
+  serial: string;
+  notBefore: string;
+  notAfter: string;
+  currentlyValid: boolean;
+  selfSigned: boolean;
+  dnsNames: string[];
+  sha256Fingerprint: string;
+  pem: string;
+}
+
+export interface TlsChainReport {
+  host: string;
+  port: number;
+  viaProxy: boolean;
+  protocolVersion: string | null;
+  cipherSuite: string | null;
+  /** Leaf first, in the order the server sent them. */
+  certificates: CertificateInfo[];
+  /** Whether HalalDL's HTTP client, including custom CAs, accepts the chain. */
+  trusted: boolean;
+  trustError: string | null;
+}
+
 export interface NativeWindowsToastPayload {
   title: string;
   body: string;
@@ -25,6 +68,30 @@ export async function syncRuntimeSettings(payload: RuntimeSettingsSyncPayload) {
   return invoke("sync_runtime_settings", { payload });
 }
 
+export async function syncBandwidthLimits(payload: BandwidthLimitsPayload) {
+  return invoke("sync_bandwidth_limits", { payload });
+}
+
+export async function syncNetworkSettings(payload: NetworkSettingsPayload) {
+  return invoke("sync_network_settings", { payload });
+}
+
+export async function saveProxyPassword(password: string) {
+  return invoke("save_proxy_password", { password });
+}
+
+export async function hasProxyPassword(): Promise<boolean> {
+  return invoke<boolean>("has_proxy_password");
+}
+
+export async function removeProxyPassword() {
+  return invoke("remove_proxy_password");
+}
+
+export async function inspectTlsCertificateChain(host: string, port?: number): Promise<TlsChainReport> {
+  return invoke<TlsChainReport>("inspect_tls_certificate_chain", { host, port: port ?? null });
+}
+
 export async function updateTrayState(payload: TrayStatePayload) {
   return invoke("update_tray_state", { payload });
 }
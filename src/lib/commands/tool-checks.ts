@@ -31,7 +31,9 @@ export async function resolveSystemToolPath(tool: string): Promise<string | null
 
 export { resolveSystemToolPaths, probeExecutableVersion } from "@/lib/downloader/js-runtime";
 
-/** Cheap path heuristic: pip installs land under Scripts or site-packages. */
+const PACKAGE_MANAGED_DIRS = ["/scripts/", "/site-packages/", "/pipx/", "/uv/tools/", "/.local/bin/", "/cellar/yt-dlp/"];
+
+/** Cheap path heuristic: pip, pipx, uv and Homebrew installs land under these folders. */
 export function isYtDlpPipPath(path?: string | null): boolean {
   if (!path) return false;
   const lower = path.toLowerCase().replace(/\\/g, "/");
@@ -39,7 +41,7 @@ export function isYtDlpPipPath(path?: string | null): boolean {
   if (!(base === "yt-dlp" || base === "yt-dlp.exe" || base.startsWith("yt-dlp."))) {
     return false;
   }
-  return lower.includes("/scripts/") || lower.includes("/site-packages/");
+  return PACKAGE_MANAGED_DIRS.some((dir) => lower.includes(dir));
 }
 
 export function isPipYtDlpTool(tool: {
@@ -375,12 +377,28 @@ export async function checkDenoVersion(): Promise<ToolCheckResult | null> {
   return null;
 }
 
+export type YtDlpInstallMethod = "pipx" | "uv-tool" | "venv" | "conda" | "homebrew" | "distro" | "pip" | "unknown";
+
+export interface YtDlpInstall {
+  path: string;
+  method: YtDlpInstallMethod;
+  /** Program and arguments that upgrade this install; null when it cannot be upgraded here. */
+  upgradeCommand: string[] | null;
+  reason: string | null;
+}
+
+/** How the yt-dlp at `systemPath` (or first on PATH) was installed and how it upgrades. */
+export async function detectYtDlpInstall(systemPath?: string | null): Promise<YtDlpInstall> {
+  return invoke<YtDlpInstall>("detect_ytdlp_install_method", { systemPath: systemPath ?? null });
+}
+
+/** Upgrade a system yt-dlp with the package manager that installed it (pip, pipx, uv, conda, Homebrew). */
 export async function upgradeYtDlpViaPip(systemPath?: string | null): Promise<boolean> {
   const { addLog } = useLogsStore.getState();
   try {
     addLog({
       level: "command",
-      message: "Upgrading yt-dlp via pip...",
+      message: "Upgrading system yt-dlp...",
       command: `invoke("upgrade_ytdlp_via_pip", { systemPath: ${JSON.stringify(systemPath ?? null)} })`,
     });
     const result = await invoke<string>("upgrade_ytdlp_via_pip", {
@@ -389,29 +407,7 @@ export async function upgradeYtDlpViaPip(systemPath?: string | null): Promise<bo
     addLog({ level: "info", message: result });
     return true;
   } catch (e) {
-    addLog({ level: "error", message: `pip upgrade failed: ${String(e)}` });
-    const { Command } = await import("@tauri-apps/plugin-shell");
-    for (const pipCmd of ["pip", "pip3"]) {
-      try {
-        addLog({
-          level: "command",
-          message: `Upgrading yt-dlp via ${pipCmd}...`,
-          command: `${pipCmd} install --upgrade yt-dlp`,
-        });
-        const cmd = Command.create(pipCmd, ["install", "--upgrade", "yt-dlp"]);
-        const output = await cmd.execute();
-        if (output.code === 0) {
-          addLog({ level: "info", message: `yt-dlp upgraded via ${pipCmd}` });
-          return true;
-        }
-        addLog({
-          level: "warn",
-          message: `${pipCmd} upgrade returned code ${output.code}: ${output.stderr}`,
-        });
-      } catch (err) {
-        addLog({ level: "debug", message: `${pipCmd} not available: ${String(err)}` });
-      }
-    }
+    addLog({ level: "error", message: `yt-dlp upgrade failed: ${String(e)}` });
     return false;
   }
 }
@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { open as openDialog } from "@tauri-apps/plugin-dialog";
 import { useLogsStore } from "@/store/logs";
+import { useSettingsStore } from "@/store/settings";
 import { useToolsStore } from "@/store/tools";
 import { revealInExplorer } from "./file-commands";
 import type { ToolBatchResult } from "@/lib/tools/tool-batch";
@@ -8,16 +9,60 @@ import { getAppPaths } from "@/lib/app-paths";
 import { resolveTool } from "@/lib/downloader/tool-env";
 import { runResolvedTool } from "@/lib/process/app-bin";
 
-export async function updateToolAtPath(tool: string, destDir: string, variant?: string, channel?: string): Promise<string> {
+/** `version` installs and pins a release tag ("latest" clears the pin); without it a pinned release is kept. */
+export async function updateToolAtPath(tool: string, destDir: string, variant?: string, channel?: string, background?: boolean, version?: string): Promise<string> {
   const { addLog } = useLogsStore.getState();
-  addLog({ level: "command", message: `Updating ${tool} at ${destDir} (variant: ${variant || "default"}, channel: ${channel || "stable"})` });
-  return invoke<string>("update_tool_at_path", { tool, destDir, variant: variant ?? null, channel: channel ?? null });
+  addLog({ level: "command", message: `Updating ${tool} at ${destDir} (variant: ${variant || "default"}, channel: ${channel || "stable"}, version: ${version || "pinned or latest"})` });
+  return invoke<string>("update_tool_at_path", { tool, destDir, variant: variant ?? null, channel: channel ?? null, version: version ?? null, background: background ?? null });
 }
 
-export async function downloadTools(tools: string[], channels?: Record<string, string>): Promise<ToolBatchResult> {
+export type PreflightCheckKind = "writable" | "disk-space" | "running-processes" | "package-manager";
+
+export interface PreflightCheck {
+  kind: PreflightCheckKind;
+  passed: boolean;
+  detail: string;
+}
+
+export interface PlannedFile {
+  name: string;
+  path: string;
+  /** false when the update would create the file rather than replace it. */
+  exists: boolean;
+  size: number | null;
+}
+
+export interface ToolUpdatePlan {
+  tool: string;
+  destDir: string;
+  files: PlannedFile[];
+  estimatedBytes: number;
+  availableBytes: number | null;
+  checks: PreflightCheck[];
+  /** Every check passed; `updateToolAtPath` refuses to start otherwise. */
+  ready: boolean;
+}
+
+/** Dry run of `updateToolAtPath`: writability, free space, running processes and package-manager ownership. Without `variant` the recorded one is planned. */
+export async function preflightToolUpdate(tool: string, destDir: string, variant?: string): Promise<ToolUpdatePlan> {
+  return invoke<ToolUpdatePlan>("preflight_tool_update", { tool, destDir, variant: variant ?? null });
+}
+
+/** `variants` maps a tool id to a build variant (FFmpeg: "essentials", "full" or "shared"); tools without one keep their recorded variant. */
+/**
+ * Installs run in parallel, `concurrency` at a time (the `toolInstallConcurrency` setting by default); results keep the order of `tools`.
+ * `versions` maps tool ids to release tags to install and pin ("latest" clears a pin).
+ */
+export async function downloadTools(tools: string[], channels?: Record<string, string>, background?: boolean, variants?: Record<string, string>, concurrency?: number, versions?: Record<string, string>): Promise<ToolBatchResult> {
   const { addLog } = useLogsStore.getState();
+  const limit = concurrency ?? useSettingsStore.getState().settings.toolInstallConcurrency;
   addLog({ level: "command", message: `Downloading tools: ${tools.join(", ") || "(none)"}`, command: `invoke("download_tools", { tools: ${JSON.stringify(tools)} })` });
-  return await invoke<ToolBatchResult>("download_tools", { tools, channels: channels ?? null });
+  return await invoke<ToolBatchResult>("download_tools", { tools, channels: channels ?? null, variants: variants ?? null, versions: versions ?? null, background: background ?? null, concurrency: limit ?? null });
+}
+
+/** Cancel a running or queued tool install/update (tool id), the app update ("app-update") or a segmented download (its progress id). */
+export async function cancelToolDownload(id: string): Promise<boolean> {
+  return invoke<boolean>("cancel_tool_download", { id });
 }
 
 export async function stageManualTool(tool: string, source: string): Promise<string> {
@@ -26,6 +71,176 @@ export async function stageManualTool(tool: string, source: string): Promise<str
   return await invoke("stage_manual_tool", { tool, source });
 }
 
+export interface ToolBundleFile {
+  name: string;
+  sha256: string;
+  size: number;
+}
+
+export interface ToolBundleEntry {
+  id: string;
+  version: string | null;
+  files: ToolBundleFile[];
+  /** Shared libraries installed next to the binaries (FFmpeg shared builds). */
+  libraries: ToolBundleFile[];
+}
+
+export interface ToolBundleResult {
+  /** Bundle file for exports, app bin dir for imports. */
+  path: string;
+  tools: ToolBundleEntry[];
+}
+
+/** Pack app-managed tools (all installed ones by default) into an offline bundle zip. */
+export async function exportToolBundle(outputPath: string, tools?: string[]): Promise<ToolBundleResult> {
+  const { addLog } = useLogsStore.getState();
+  addLog({ level: "command", message: `Exporting tool bundle to ${outputPath}` });
+  return invoke<ToolBundleResult>("export_tool_bundle", { outputPath, tools: tools ?? null });
+}
+
+/** Install tools from an offline bundle after checking every file against its manifest. */
+export async function importToolBundle(bundlePath: string, tools?: string[]): Promise<ToolBundleResult> {
+  const { addLog } = useLogsStore.getState();
+  addLog({ level: "command", message: `Importing tool bundle from ${bundlePath}` });
+  return invoke<ToolBundleResult>("import_tool_bundle", { bundlePath, tools: tools ?? null });
+}
+
+export interface ToolManifestFile {
+  name: string;
+  sha256: string;
+  size: number;
+}
+
+export interface ToolManifestEntry {
+  tool: string;
+  installDir: string | null;
+  version: string | null;
+  channel: string | null;
+  /** Build variant ("essentials", "full", "shared"), kept by later updates. */
+  variant: string | null;
+  sourceUrl: string | null;
+  sha256: string | null;
+  /** Milliseconds since the Unix epoch. */
+  installedAt: number;
+  method: "download" | "manual" | "bundle" | "pip" | "package-manager";
+  files: ToolManifestFile[];
+  /** Shared libraries installed next to the binaries; also listed in `files`. */
+  libraries: string[];
+  /** The install this one replaced (restored by rollback). */
+  previous?: ToolManifestEntry;
+}
+
+export interface ToolManifest {
+  version: number;
+  tools: ToolManifestEntry[];
+}
+
+/** Installed tools as recorded in tools-manifest.json in the state dir. */
+export async function getToolManifest(): Promise<ToolManifest> {
+  return invoke<ToolManifest>("get_tool_manifest");
+}
+
+export interface ToolRelease {
+  /** Pass as the version to `downloadTools` / `updateToolAtPath`. */
+  tag: string;
+  name: string | null;
+  publishedAt: string | null;
+  prerelease: boolean;
+  pinned: boolean;
+}
+
+export interface ToolPin {
+  version: string;
+  pinnedAt: number;
+}
+
+/** Recent upstream releases of a tool (newest first), to pick a version to pin. */
+export async function listAvailableToolVersions(tool: string, channel?: string, variant?: string, limit?: number): Promise<ToolRelease[]> {
+  return invoke<ToolRelease[]>("list_available_tool_versions", { tool, channel: channel ?? null, variant: variant ?? null, limit: limit ?? null });
+}
+
+/** Release tag per pinned tool id; update checks report the pin instead of the newest release. */
+export async function getToolPins(): Promise<Record<string, ToolPin>> {
+  return invoke<Record<string, ToolPin>>("get_tool_pins");
+}
+
+/** Pin a tool to `version` without installing it; null or "latest" clears the pin. */
+export async function setToolPin(tool: string, version: string | null): Promise<void> {
+  const { addLog } = useLogsStore.getState();
+  addLog({ level: "command", message: version ? `Pinning ${tool} to ${version}` : `Unpinning ${tool}` });
+  await invoke("set_tool_pin", { tool, version });
+}
+
+export interface ToolVersion {
+  /** Version line the tool printed. */
+  raw: string;
+  version: string;
+  channel: "stable" | "nightly";
+  /** FFmpeg build flavor: essentials, full, shared or static. */
+  flavor: string | null;
+}
+
+export interface ToolVersionStatus {
+  tool: string;
+  path: string;
+  installed: ToolVersion | null;
+  latest: ToolVersion | null;
+  /** null when either version is unknown or they are on different channels. */
+  outdated: boolean | null;
+  latestError: string | null;
+}
+
+/** Parsed version of a managed or system tool binary; null when it is missing or does not run. */
+export async function probeToolVersion(path: string): Promise<ToolVersion | null> {
+  return invoke<ToolVersion | null>("probe_tool_version", { path });
+}
+
+/** Compare a tool binary (the app-managed one by default) with its latest or pinned release. */
+export async function checkToolVersion(tool: string, path?: string, channel?: string): Promise<ToolVersionStatus> {
+  return invoke<ToolVersionStatus>("check_tool_version", { tool, path: path ?? null, channel: channel ?? null });
+}
+
+export type BinaryStatus = "ok" | "missing" | "modified" | "zero-byte";
+export type IntegrityRepair = "restore" | "reinstall";
+
+export interface BinaryIntegrity {
+  tool: string;
+  name: string;
+  path: string;
+  status: BinaryStatus;
+  expectedSize: number;
+  actualSize: number | null;
+  expectedSha256: string;
+  actualSha256: string | null;
+}
+
+export interface ToolRepairResult {
+  tool: string;
+  action: IntegrityRepair;
+  success: boolean;
+  message: string;
+}
+
+export interface IntegrityReport {
+  binDir: string;
+  /** State after any repairs. */
+  binaries: BinaryIntegrity[];
+  repairs: ToolRepairResult[];
+  /** Interrupted updates found and undone at startup. */
+  recovered: string[];
+  allOk: boolean;
+}
+
+/** Check managed binaries against the tool manifest, optionally restoring or reinstalling damaged ones. */
+export async function scanToolIntegrity(repair?: IntegrityRepair): Promise<IntegrityReport> {
+  return invoke<IntegrityReport>("scan_tool_integrity", { repair: repair ?? null });
+}
+
+/** Most recent integrity scan (the one started at launch until another runs). */
+export async function getToolIntegrityReport(): Promise<IntegrityReport | null> {
+  return invoke<IntegrityReport | null>("get_tool_integrity_report");
+}
+
 const TOOL_BIN_NAMES: Record<string, { windows: string; unix: string }> = {
   "yt-dlp": { windows: "yt-dlp.exe", unix: "yt-dlp" },
   "ffmpeg": { windows: "ffmpeg.exe", unix: "ffmpeg" },
@@ -34,12 +249,14 @@ const TOOL_BIN_NAMES: Record<string, { windows: string; unix: string }> = {
 };
 
 export async function pickFile(): Promise<string | null> {
+  // Linux/macOS binaries have no extension, so only Windows can filter by one.
+  const isWindows = navigator.userAgent.toLowerCase().includes("windows");
   const selected = await openDialog({
     multiple: false,
-    filters: [{
+    filters: isWindows ? [{
       name: 'Executable',
       extensions: ['exe']
-    }]
+    }] : undefined
   });
   if (Array.isArray(selected)) return selected[0];
   return selected;
@@ -76,12 +293,23 @@ function getExtraBackupPaths(): string[] {
   return tools.filter((t) => t.systemPath).map((t) => t.systemPath!);
 }
 
-export async function listToolBackups(): Promise<string[]> {
-  return invoke<string[]>("list_tool_backups", { extraPaths: getExtraBackupPaths() });
+export interface ToolBackup {
+  tool: string;
+  /** Null for a single `.old` backup next to the binary. */
+  version: string | null;
+  /** Milliseconds since the Unix epoch. */
+  createdAt: number;
+  path: string;
 }
 
-export async function rollbackTool(tool: string): Promise<string> {
-  return invoke<string>("rollback_tool", { tool, extraPaths: getExtraBackupPaths() });
+/** Versioned backups in the app bin dir, then `.old` backups next to system binaries. */
+export async function listToolBackups(): Promise<ToolBackup[]> {
+  return invoke<ToolBackup[]>("list_tool_backups", { extraPaths: getExtraBackupPaths() });
+}
+
+/** Restore backup `version` of a tool, or the newest one when omitted. */
+export async function rollbackTool(tool: string, version?: string): Promise<string> {
+  return invoke<string>("rollback_tool", { tool, extraPaths: getExtraBackupPaths(), version: version ?? null });
 }
 
 export async function cleanupToolBackup(tool: string): Promise<string> {
@@ -128,6 +356,25 @@ export async function downloadUrlToFile(url: string, dest: string, referer?: str
   });
 }
 
+/** Multi-connection fetch for large direct media URLs; falls back to one stream when ranges are unsupported. */
+export async function downloadUrlSegmented(
+  url: string,
+  dest: string,
+  referer?: string,
+  progressId?: string
+): Promise<string> {
+  const { userAgent, acceptLanguage } = getBrowserRequestHeaders();
+  return invoke<string>("download_url_segmented", {
+    url,
+    dest,
+    connections: null,
+    progressId: progressId ?? null,
+    referer: referer ?? null,
+    userAgent,
+    acceptLanguage,
+  });
+}
+
 export async function postFormForText(
   url: string,
   body: string,
@@ -15,7 +15,11 @@ import { runResolvedTool, spawnResolvedTool, type SpawnedProcess } from "@/lib/p
 import { formatSponsorBlockCategories } from "@/lib/sponsorblock";
 import { cleanupThumbnailByJobId } from "./thumbnails";
 import { fetchMediaInfo, fetchMetadata } from "./metadata";
-import { downloadInstagramJob } from "./instagram";
+import {
+  cancelInstagramTransfers,
+  downloadInstagramJob,
+  hasActiveInstagramTransfers,
+} from "./instagram";
 import { isInstagramUrl } from "@/lib/media-engine";
 import { useHistoryStore, extractDomain, type HistoryEntry } from "@/store/history";
 import { stat } from "@tauri-apps/plugin-fs";
@@ -232,8 +236,9 @@ export async function pauseActiveDownload(jobId: string) {
   const { addLog } = useLogsStore.getState();
   const job = jobs.find((candidate) => candidate.id === jobId);
   const child = activeYtDlpChildren.get(jobId);
+  const instagramTransfer = hasActiveInstagramTransfers(jobId);
 
-  if (!job || job.status !== "Downloading" || !child) {
+  if (!job || job.status !== "Downloading" || (!child && !instagramTransfer)) {
     return false;
   }
 
@@ -246,8 +251,13 @@ export async function pauseActiveDownload(jobId: string) {
   });
 
   try {
-    await child.kill();
-    addLog({ level: "info", message: "Pause requested for active yt-dlp download", jobId });
+    if (child) {
+      await child.kill();
+      addLog({ level: "info", message: "Pause requested for active yt-dlp download", jobId });
+    } else {
+      await cancelInstagramTransfers(jobId);
+      addLog({ level: "info", message: "Pause requested for active Instagram download", jobId });
+    }
     return true;
   } catch (error) {
     holdRequestedJobs.delete(jobId);
@@ -686,7 +696,7 @@ export async function startDownload(jobId: string) {
 
     if (instagramResult.code === 0) {
       await finalizeSuccessfulDownload(instagramResult.lastKnownOutputPath, instagramResult.outputPaths);
-    } else {
+    } else if (!(await finalizeHoldRequest())) {
       await finalizeFailedDownload(instagramResult.failDetail);
     }
     return;
@@ -781,7 +791,7 @@ export async function startDownload(jobId: string) {
   }
 
   if (ffmpeg.isLocal) {
-    const ffmpegDir = ffmpeg.path.replace(/\\ffmpeg\.exe$/, "");
+    const ffmpegDir = ffmpeg.path.replace(/[\\/]ffmpeg(\.exe)?$/i, "");
     args.push("--ffmpeg-location", ffmpegDir);
   }
 
@@ -795,6 +805,12 @@ export async function startDownload(jobId: string) {
         "Using yt-dlp native downloader for YouTube — aria2’s many connections often trigger HTTP 429",
       jobId,
     });
+  } else if (aria2.isLocal && settings.aria2Enabled && /^socks/i.test(settings.proxyUrl)) {
+    addLog({
+      level: "warn",
+      message: "aria2 cannot use a SOCKS proxy — using yt-dlp native downloader so the download stays on the proxy",
+      jobId,
+    });
   } else if (aria2.isLocal && settings.aria2Enabled) {
     addLog({ level: "info", message: `Using local aria2c: ${aria2.path}`, jobId });
     args.push("--external-downloader", aria2.path);
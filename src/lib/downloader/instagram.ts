@@ -1,10 +1,17 @@
+import { listen } from "@tauri-apps/api/event";
 import { downloadDir as defaultDownloadDir, join } from "@tauri-apps/api/path";
 import { exists, mkdir } from "@tauri-apps/plugin-fs";
 import type { DownloadJob } from "@/store/downloads";
 import type { Preset } from "@/store/presets";
 import type { Settings } from "@/store/settings";
 import type { SubtitlePreferences } from "@/lib/subtitles";
-import { deleteFile, downloadUrlToFile, writeTextFile } from "@/lib/commands";
+import {
+  cancelToolDownload,
+  deleteFile,
+  downloadUrlSegmented,
+  writeTextFile,
+} from "@/lib/commands";
+import { formatBytes, formatDuration } from "@/lib/media-properties";
 import { ensureFilenameTemplateExtension } from "@/lib/output-paths";
 import {
   extractInstagramShortcode,
@@ -60,12 +67,33 @@ type PlannedInstagramItem = {
   shouldPostProcess: boolean;
 };
 
+type SegmentedProgressEvent = {
+  tool: string;
+  bytesPerSecond?: number;
+  etaSeconds?: number;
+};
+
 const INSTAGRAM_CAROUSEL_DOWNLOAD_CONCURRENCY = 2;
 const INSTAGRAM_STATUS_PROGRESS_SHARE = {
   downloadOnly: 95,
   withPostProcess: 70,
 } as const;
 
+const activeInstagramTransfers = new Map<string, Set<string>>();
+
+export function hasActiveInstagramTransfers(jobId: string) {
+  return (activeInstagramTransfers.get(jobId)?.size ?? 0) > 0;
+}
+
+/** Cancel the media fetches running for an Instagram job. */
+export async function cancelInstagramTransfers(jobId: string) {
+  const transferIds = Array.from(activeInstagramTransfers.get(jobId) ?? []);
+  const results = await Promise.all(
+    transferIds.map((transferId) => cancelToolDownload(transferId))
+  );
+  return results.some(Boolean);
+}
+
 export async function downloadInstagramJob(options: {
   job: DownloadJob;
   preset: Preset;
@@ -283,22 +311,52 @@ export async function downloadInstagramJob(options: {
   }
 
   let completedDownloads = 0;
+  const transferIds = new Set<string>();
+  const transferRates = new Map<string, SegmentedProgressEvent>();
+  activeInstagramTransfers.set(job.id, transferIds);
+  const unlistenProgress = await listen<SegmentedProgressEvent>(
+    "download-progress",
+    (event) => {
+      if (!transferIds.has(event.payload.tool)) return;
+      transferRates.set(event.payload.tool, event.payload);
+      const rates = Array.from(transferRates.values());
+      const bytesPerSecond = rates.reduce(
+        (sum, rate) => sum + (rate.bytesPerSecond ?? 0),
+        0
+      );
+      const etaSeconds = Math.max(0, ...rates.map((rate) => rate.etaSeconds ?? 0));
+      updateJob(job.id, {
+        speed: bytesPerSecond > 0 ? `${formatBytes(bytesPerSecond)}/s` : undefined,
+        eta: formatDuration(etaSeconds),
+      });
+    }
+  );
   try {
     await runWithConcurrency(
       plannedItems,
       INSTAGRAM_CAROUSEL_DOWNLOAD_CONCURRENCY,
       async (planned) => {
+        // A failed or cancelled sibling already settled the job.
+        if (activeInstagramTransfers.get(job.id) !== transferIds) return;
         addLog({
           level: "info",
           message: `Downloading Instagram ${planned.item.type} ${planned.item.index + 1}/${resolved.items.length} → ${planned.destination}`,
           jobId: job.id,
         });
 
-        await downloadUrlToFile(
-          planned.item.downloadUrl,
-          planned.downloadPath,
-          job.url
-        );
+        const transferId = `${job.id}:${planned.item.index}`;
+        transferIds.add(transferId);
+        try {
+          await downloadUrlSegmented(
+            planned.item.downloadUrl,
+            planned.downloadPath,
+            job.url,
+            transferId
+          );
+        } finally {
+          transferIds.delete(transferId);
+          transferRates.delete(transferId);
+        }
         wroteAny = true;
         firstWrittenPath ??= planned.destination;
         completedDownloads += 1;
@@ -339,6 +397,10 @@ export async function downloadInstagramJob(options: {
       code: 1,
       failDetail: `Instagram download failed: ${String(error)}`,
     };
+  } finally {
+    unlistenProgress();
+    activeInstagramTransfers.delete(job.id);
+    updateJob(job.id, { speed: undefined, eta: undefined });
   }
 
   const postProcessItems = plannedItems.filter(
@@ -1,7 +1,39 @@
+/** Why the backend refused an archive or file (`Rejected [kind]: ...` errors). */
+export type ArchiveRejectionKind =
+  | "too-many-entries"
+  | "entry-too-large"
+  | "total-too-large"
+  | "compression-ratio"
+  | "insufficient-disk-space";
+
+const ARCHIVE_REJECTION_HINTS: Record<ArchiveRejectionKind, string> = {
+  "too-many-entries": "The archive contains far more files than a tool release should.",
+  "entry-too-large": "A file in it is larger than any tool binary should be.",
+  "total-too-large": "It unpacks to far more data than a tool release should.",
+  "compression-ratio": "It is compressed suspiciously well, which is typical of a zip bomb.",
+  "insufficient-disk-space": "Free up disk space on that drive and try again.",
+};
+
+export function getArchiveRejectionKind(message: string): ArchiveRejectionKind | null {
+  const kind = /Rejected \[([a-z-]+)\]/.exec(message)?.[1];
+  return kind && kind in ARCHIVE_REJECTION_HINTS ? (kind as ArchiveRejectionKind) : null;
+}
+
+/** `message` with an explanation appended when it is an archive rejection. */
+export function explainToolError(message: string): string {
+  const kind = getArchiveRejectionKind(message);
+  return kind ? `${message}. ${ARCHIVE_REJECTION_HINTS[kind]}` : message;
+}
+
 export interface ToolBatchItemResult {
   tool: string;
   success: boolean;
+  cancelled: boolean;
+  /** Archive limit the download tripped, if any. */
+  rejection: ArchiveRejectionKind | null;
   message: string;
+  /** SHA-256 of the verified download (the archive for zip-packaged tools). */
+  sha256: string | null;
 }
 
 export interface ToolBatchResult {
@@ -15,7 +47,11 @@ export function getSuccessfulToolResults(result: ToolBatchResult | null): ToolBa
 }
 
 export function getFailedToolResults(result: ToolBatchResult | null): ToolBatchItemResult[] {
-  return result?.results.filter((item) => !item.success) ?? [];
+  return result?.results.filter((item) => !item.success && !item.cancelled) ?? [];
+}
+
+export function getCancelledToolResults(result: ToolBatchResult | null): ToolBatchItemResult[] {
+  return result?.results.filter((item) => item.cancelled) ?? [];
 }
 
 export function buildToolBatchErrorMessage(
@@ -27,9 +63,10 @@ export function buildToolBatchErrorMessage(
 
   const first = failed[0];
   const toolName = toolNameById[first.tool] ?? first.tool;
+  const firstMessage = explainToolError(first.message);
   if (failed.length === 1) {
-    return `${toolName} failed: ${first.message}`;
+    return `${toolName} failed: ${firstMessage}`;
   }
 
-  return `${failed.length} tools failed. First issue: ${toolName} — ${first.message}`;
+  return `${failed.length} tools failed. First issue: ${toolName} — ${firstMessage}`;
 }
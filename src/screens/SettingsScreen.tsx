@@ -21,6 +21,7 @@ import { EngineSection } from "./settings/components/EngineSection";
 import { PerformanceSection } from "./settings/components/PerformanceSection";
 import { AboutSection } from "./settings/components/AboutSection";
 import { AiConnectionsSection } from "./settings/components/AiConnectionsSection";
+import { NetworkSection } from "./settings/components/NetworkSection";
 import { usePresetsStore } from "@/store/presets";
 import { getQuickEligiblePresets } from "@/lib/preset-display";
 import { storage } from "@/lib/storage";
@@ -432,6 +433,8 @@ export function SettingsScreen() {
             <EngineSection
               maxConcurrency={draftSettings.maxConcurrency}
               onMaxConcurrencyChange={(v) => setDraftValue("maxConcurrency", v)}
+              toolInstallConcurrency={draftSettings.toolInstallConcurrency}
+              onToolInstallConcurrencyChange={(v) => setDraftValue("toolInstallConcurrency", v)}
               maxRetries={draftSettings.maxRetries}
               onMaxRetriesChange={(v) => setDraftValue("maxRetries", v)}
               maxSpeed={draftSettings.maxSpeed}
@@ -446,6 +449,25 @@ export function SettingsScreen() {
               onInstagramEngineChange={(v) => setDraftValue("instagramEngine", v)}
             />
 
+            <NetworkSection
+              proxyUrl={draftSettings.proxyUrl}
+              onProxyUrlChange={(v) => setDraftValue("proxyUrl", v)}
+              proxyUsername={draftSettings.proxyUsername}
+              onProxyUsernameChange={(v) => setDraftValue("proxyUsername", v)}
+              connectTimeoutSecs={draftSettings.connectTimeoutSecs}
+              onConnectTimeoutSecsChange={(v) => setDraftValue("connectTimeoutSecs", v)}
+              readTimeoutSecs={draftSettings.readTimeoutSecs}
+              onReadTimeoutSecsChange={(v) => setDraftValue("readTimeoutSecs", v)}
+              bindAddress={draftSettings.bindAddress}
+              onBindAddressChange={(v) => setDraftValue("bindAddress", v)}
+              caCertificatesPath={draftSettings.caCertificatesPath}
+              onCaCertificatesPathChange={(v) => setDraftValue("caCertificatesPath", v)}
+              toolDownloadMaxSpeed={draftSettings.toolDownloadMaxSpeed}
+              onToolDownloadMaxSpeedChange={(v) => setDraftValue("toolDownloadMaxSpeed", v)}
+              backgroundToolDownloadMaxSpeed={draftSettings.backgroundToolDownloadMaxSpeed}
+              onBackgroundToolDownloadMaxSpeedChange={(v) => setDraftValue("backgroundToolDownloadMaxSpeed", v)}
+            />
+
             <AiConnectionsSection />
 
             <PerformanceSection />
@@ -198,17 +198,17 @@ export function AboutSection() {
 
     setIsDownloadingUpdate(true);
     try {
-      const verifiedPath = await downloadAndVerifyAppUpdate({
+      const verified = await downloadAndVerifyAppUpdate({
         downloadUrl,
         assetName,
         checksumUrl,
       });
       // Store readiness before notifying. A native-toast click may switch back
       // to this view, and local component state would be lost on that remount.
-      setVerifiedInstallerPath(verifiedPath);
+      setVerifiedInstallerPath(verified.path);
       await notifyUser(
         "HalalDL update ready",
-        "The verified installer has been downloaded and is ready to run.",
+        `The installer (signed by release key ${verified.keyId}) has been downloaded and is ready to run.`,
         "success",
         {
           screen: "settings",
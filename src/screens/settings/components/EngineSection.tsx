@@ -1,4 +1,4 @@
-import { Cookie, Gauge, Layers, RotateCcw, Zap, Trash2, Images, Search, X } from "lucide-react";
+import { Cookie, Gauge, Layers, RotateCcw, Zap, Trash2, Images, Search, Wrench, X } from "lucide-react";
 import { Slider } from "@/components/ui/slider";
 import { Input } from "@/components/ui/input";
 import {
@@ -17,7 +17,7 @@ import { clearYtDlpCache } from "@/lib/commands";
 import { MotionButton } from "@/components/motion/MotionButton";
 import { SponsorBlockControls } from "@/components/SponsorBlockControls";
 import type { SponsorBlockCategoryId } from "@/lib/sponsorblock";
-import type { InstagramEngine, SponsorBlockMode } from "@/store/settings";
+import { TOOL_INSTALL_CONCURRENCY_MAX, type InstagramEngine, type SponsorBlockMode } from "@/store/settings";
 import { SettingsSection } from "./SettingsSection";
 import { SettingRow } from "./SettingRow";
 
@@ -31,6 +31,8 @@ const SPEED_UNITS = [
 interface EngineSectionProps {
   maxConcurrency: number;
   onMaxConcurrencyChange: (val: number) => void;
+  toolInstallConcurrency: number;
+  onToolInstallConcurrencyChange: (val: number) => void;
   maxRetries: number;
   onMaxRetriesChange: (val: number) => void;
   maxSpeed: number;
@@ -47,6 +49,7 @@ interface EngineSectionProps {
 
 export function EngineSection({
   maxConcurrency, onMaxConcurrencyChange,
+  toolInstallConcurrency, onToolInstallConcurrencyChange,
   maxRetries, onMaxRetriesChange,
   maxSpeed, onMaxSpeedChange,
   cookiesFilePath, onCookiesFilePathChange,
@@ -128,6 +131,31 @@ export function EngineSection({
         </div>
       </SettingRow>
 
+      <SettingRow icon={Wrench} label="Parallel Tool Installs" description="Number of tools (yt-dlp, FFmpeg, aria2, Deno) to install or update at once." vertical>
+        <div className="flex items-center gap-4">
+          <Slider
+            value={[toolInstallConcurrency]}
+            min={1}
+            max={TOOL_INSTALL_CONCURRENCY_MAX}
+            step={1}
+            onValueChange={([v]: number[]) => onToolInstallConcurrencyChange(v)}
+            className="flex-1"
+          />
+          <AnimatePresence mode="popLayout">
+            <motion.span
+              key={toolInstallConcurrency}
+              initial={{ y: -8, opacity: 0 }}
+              animate={{ y: 0, opacity: 1 }}
+              exit={{ y: 8, opacity: 0 }}
+              transition={{ duration: 0.15 }}
+              className="font-mono font-bold text-primary text-sm w-6 text-center"
+            >
+              {toolInstallConcurrency}
+            </motion.span>
+          </AnimatePresence>
+        </div>
+      </SettingRow>
+
       <SettingRow icon={RotateCcw} label="Max Retries" description="Number of attempts if a download fails." vertical>
         <div className="flex items-center gap-4">
           <Slider
@@ -0,0 +1,293 @@
+import { useEffect, useState } from "react";
+import { Clock, Download, FileKey, KeyRound, Network, Search, Server, ShieldCheck, Trash2, User, X } from "lucide-react";
+import { toast } from "sonner";
+import { open } from "@tauri-apps/plugin-dialog";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import {
+  hasProxyPassword,
+  inspectTlsCertificateChain,
+  removeProxyPassword,
+  saveProxyPassword,
+  type TlsChainReport,
+} from "@/lib/commands";
+import { SettingsSection } from "./SettingsSection";
+import { SettingRow } from "./SettingRow";
+
+interface NetworkSectionProps {
+  proxyUrl: string;
+  onProxyUrlChange: (val: string) => void;
+  proxyUsername: string;
+  onProxyUsernameChange: (val: string) => void;
+  connectTimeoutSecs: number;
+  onConnectTimeoutSecsChange: (val: number) => void;
+  readTimeoutSecs: number;
+  onReadTimeoutSecsChange: (val: number) => void;
+  bindAddress: string;
+  onBindAddressChange: (val: string) => void;
+  caCertificatesPath: string;
+  onCaCertificatesPathChange: (val: string) => void;
+  toolDownloadMaxSpeed: number;
+  onToolDownloadMaxSpeedChange: (val: number) => void;
+  backgroundToolDownloadMaxSpeed: number;
+  onBackgroundToolDownloadMaxSpeedChange: (val: number) => void;
+}
+
+function parseWholeNumber(raw: string): number {
+  const value = Number.parseInt(raw, 10);
+  return Number.isFinite(value) && value > 0 ? value : 0;
+}
+
+export function NetworkSection({
+  proxyUrl, onProxyUrlChange,
+  proxyUsername, onProxyUsernameChange,
+  connectTimeoutSecs, onConnectTimeoutSecsChange,
+  readTimeoutSecs, onReadTimeoutSecsChange,
+  bindAddress, onBindAddressChange,
+  caCertificatesPath, onCaCertificatesPathChange,
+  toolDownloadMaxSpeed, onToolDownloadMaxSpeedChange,
+  backgroundToolDownloadMaxSpeed, onBackgroundToolDownloadMaxSpeedChange,
+}: NetworkSectionProps) {
+  const [password, setPassword] = useState("");
+  const [passwordSaved, setPasswordSaved] = useState(false);
+  const [inspectHost, setInspectHost] = useState("github.com");
+  const [isInspecting, setIsInspecting] = useState(false);
+  const [chainReport, setChainReport] = useState<TlsChainReport | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    void hasProxyPassword()
+      .then((saved) => {
+        if (!cancelled) setPasswordSaved(saved);
+      })
+      .catch(() => {
+        if (!cancelled) setPasswordSaved(false);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  const savePassword = async () => {
+    if (!password) return toast.error("Type the proxy password first.");
+    try {
+      await saveProxyPassword(password);
+      setPassword("");
+      setPasswordSaved(true);
+      toast.success("Proxy password saved securely");
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : String(error));
+    }
+  };
+
+  const erasePassword = async () => {
+    try {
+      await removeProxyPassword();
+      setPasswordSaved(false);
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : String(error));
+    }
+  };
+
+  const inspectChain = async () => {
+    // Accept a pasted URL as well as a bare host or host:port.
+    const target = inspectHost.trim().replace(/^[a-z]+:\/\//i, "").split("/")[0];
+    if (!target) return toast.error("Type a host name to check, for example github.com.");
+    const [host, rawPort] = target.split(":");
+    const port = rawPort ? Number.parseInt(rawPort, 10) : undefined;
+    setIsInspecting(true);
+    try {
+      setChainReport(await inspectTlsCertificateChain(host, Number.isFinite(port) ? port : undefined));
+    } catch (error) {
+      setChainReport(null);
+      toast.error(error instanceof Error ? error.message : String(error));
+    } finally {
+      setIsInspecting(false);
+    }
+  };
+
+  return (
+    <SettingsSection id="network" icon={Network} title="Network" description="Proxy and connection limits for HalalDL, yt-dlp, aria2 and tool updates.">
+      <SettingRow icon={Server} label="Proxy" description="http://, https://, socks5:// or socks5h:// address. Leave empty to connect directly." vertical>
+        <Input
+          value={proxyUrl}
+          onChange={(event) => onProxyUrlChange(event.target.value)}
+          placeholder="http://proxy.example.com:8080"
+          spellCheck={false}
+        />
+        {/^socks/i.test(proxyUrl.trim()) && (
+          <p className="mt-2 text-xs text-muted-foreground">aria2 cannot use SOCKS proxies, so downloads use yt-dlp's own downloader.</p>
+        )}
+      </SettingRow>
+
+      <SettingRow icon={User} label="Proxy user" description="Only if the proxy asks for a login." vertical>
+        <Input
+          value={proxyUsername}
+          onChange={(event) => onProxyUsernameChange(event.target.value)}
+          placeholder="Username"
+          autoComplete="off"
+          spellCheck={false}
+        />
+      </SettingRow>
+
+      <SettingRow icon={KeyRound} label="Proxy password" description="Stored in the system credential store, never in HalalDL settings or backups." vertical>
+        <div className="flex items-center gap-2">
+          <Input
+            value={password}
+            onChange={(event) => setPassword(event.target.value)}
+            type="password"
+            autoComplete="off"
+            placeholder={passwordSaved ? "Saved — type to replace" : "Password"}
+          />
+          <Button size="sm" onClick={() => void savePassword()}>Save</Button>
+          {passwordSaved && (
+            <Button variant="ghost" size="icon" onClick={() => void erasePassword()} aria-label="Remove proxy password">
+              <Trash2 className="h-4 w-4 text-destructive" />
+            </Button>
+          )}
+        </div>
+      </SettingRow>
+
+      <SettingRow icon={Clock} label="Timeouts" description="Seconds to wait for a connection and between reads. 0 uses the defaults (20 and 45)." vertical>
+        <div className="grid gap-2 sm:grid-cols-2">
+          <Input
+            type="number"
+            min={0}
+            value={connectTimeoutSecs}
+            onChange={(event) => onConnectTimeoutSecsChange(parseWholeNumber(event.target.value))}
+            aria-label="Connect timeout in seconds"
+          />
+          <Input
+            type="number"
+            min={0}
+            value={readTimeoutSecs}
+            onChange={(event) => onReadTimeoutSecsChange(parseWholeNumber(event.target.value))}
+            aria-label="Read timeout in seconds"
+          />
+        </div>
+      </SettingRow>
+
+      <SettingRow icon={Download} label="Tool download speed" description="KB/s limits for yt-dlp, FFmpeg and other tool downloads, and a lower one for installs that start on their own. 0 = unlimited." vertical>
+        <div className="grid gap-2 sm:grid-cols-2">
+          <Input
+            type="number"
+            min={0}
+            value={toolDownloadMaxSpeed}
+            onChange={(event) => onToolDownloadMaxSpeedChange(parseWholeNumber(event.target.value))}
+            aria-label="Tool download limit in KB/s"
+          />
+          <Input
+            type="number"
+            min={0}
+            value={backgroundToolDownloadMaxSpeed}
+            onChange={(event) => onBackgroundToolDownloadMaxSpeedChange(parseWholeNumber(event.target.value))}
+            aria-label="Background tool download limit in KB/s"
+          />
+        </div>
+      </SettingRow>
+
+      <SettingRow icon={Network} label="Bind address" description="Local IP address to connect from, e.g. to pick a network adapter. Leave empty to let the system choose." vertical>
+        <Input
+          value={bindAddress}
+          onChange={(event) => onBindAddressChange(event.target.value)}
+          placeholder="192.168.1.20"
+          spellCheck={false}
+        />
+      </SettingRow>
+
+      <SettingRow icon={FileKey} label="Extra trusted certificates" description="A PEM file or a folder of certificates, for networks that inspect HTTPS traffic. The built-in roots stay trusted." vertical>
+        <div className="flex gap-2">
+          <Input
+            value={caCertificatesPath}
+            readOnly
+            placeholder="No certificates selected"
+            className="bg-muted/30 border-border/30"
+          />
+          <Button
+            type="button"
+            variant="outline"
+            onClick={async () => {
+              const selected = await open({
+                directory: false,
+                multiple: false,
+                filters: [
+                  { name: "Certificates", extensions: ["pem", "crt", "cer", "der"] },
+                  { name: "All files", extensions: ["*"] },
+                ],
+              });
+              if (selected && !Array.isArray(selected)) onCaCertificatesPathChange(selected);
+            }}
+          >
+            <Search className="w-4 h-4 mr-2" />
+            Browse
+          </Button>
+          {caCertificatesPath ? (
+            <Button type="button" variant="outline" onClick={() => onCaCertificatesPathChange("")} aria-label="Clear extra certificates">
+              <X className="w-4 h-4" />
+            </Button>
+          ) : null}
+        </div>
+      </SettingRow>
+
+      <SettingRow icon={ShieldCheck} label="Check a site's certificate" description="Shows the certificates a site presents through your proxy, and whether HalalDL trusts them." vertical>
+        <div className="flex flex-col gap-2">
+          <div className="flex gap-2">
+            <Input
+              value={inspectHost}
+              onChange={(event) => setInspectHost(event.target.value)}
+              onKeyDown={(event) => {
+                if (event.key === "Enter") void inspectChain();
+              }}
+              placeholder="github.com"
+              spellCheck={false}
+            />
+            <Button type="button" variant="outline" disabled={isInspecting} onClick={() => void inspectChain()}>
+              {isInspecting ? "Checking..." : "Check"}
+            </Button>
+          </div>
+          {chainReport ? (
+            <div className="rounded-lg border border-border/60 bg-muted/20 p-3 text-xs">
+              <p className={chainReport.trusted ? "font-medium text-emerald-700 dark:text-emerald-300" : "font-medium text-amber-700 dark:text-amber-300"}>
+                {chainReport.trusted
+                  ? `Trusted: ${chainReport.host}:${chainReport.port}`
+                  : `Not trusted: ${chainReport.trustError ?? "unknown error"}`}
+              </p>
+              <p className="mt-1 text-muted-foreground">
+                {[chainReport.viaProxy ? "Through proxy" : "Direct", chainReport.protocolVersion, chainReport.cipherSuite]
+                  .filter(Boolean)
+                  .join(" · ")}
+              </p>
+              <div className="mt-2 divide-y divide-border/60">
+                {chainReport.certificates.map((cert) => (
+                  <div key={cert.sha256Fingerprint} className="flex items-start justify-between gap-3 py-2">
+                    <div className="min-w-0">
+                      <p className="truncate font-medium">{cert.subject}</p>
+                      <p className="truncate text-muted-foreground">Issued by {cert.issuer}</p>
+                      <p className="text-muted-foreground">
+                        {cert.notBefore} to {cert.notAfter}
+                        {cert.currentlyValid ? "" : " · expired or not yet valid"}
+                        {cert.selfSigned ? " · self-signed" : ""}
+                      </p>
+                    </div>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => {
+                        void navigator.clipboard.writeText(cert.pem).then(
+                          () => toast.success("Certificate copied as PEM"),
+                          () => toast.error("Could not copy the certificate"),
+                        );
+                      }}
+                    >
+                      Copy PEM
+                    </Button>
+                  </div>
+                ))}
+              </div>
+            </div>
+          ) : null}
+        </div>
+      </SettingRow>
+    </SettingsSection>
+  );
+}
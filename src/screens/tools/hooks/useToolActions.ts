@@ -29,6 +29,7 @@ import { getLatestTrackForTool, getLatestSourceForTool } from "../constants";
 import type { ModalApi } from "./useDownloadProgressModal";
 import {
   buildToolBatchErrorMessage,
+  explainToolError,
   getFailedToolResults,
   getSuccessfulToolResults,
 } from "@/lib/tools/tool-batch";
@@ -57,7 +58,7 @@ export function useToolActions(modalApi: ModalApi) {
   /* ── Backup helpers ── */
   const refreshBackups = useCallback(async () => {
     try {
-      const ids = await listToolBackups();
+      const ids = (await listToolBackups()).map((backup) => backup.tool);
       const { tools: current, updateTool: update } = useToolsStore.getState();
       for (const t of current) {
         update(t.id, { hasBackup: ids.includes(t.id) });
@@ -474,7 +475,7 @@ export function useToolActions(modalApi: ModalApi) {
       await autoRestartAfterUpdate(`${tool.name} was updated successfully. HalalDL will restart now.`);
     } catch (e) {
       const message = e instanceof Error ? e.message : String(e);
-      setModalError(message);
+      setModalError(explainToolError(message));
       pushModalLog(`[${tool.name}] In-place update failed: ${message}`);
       addLog({
         level: "error",
@@ -652,7 +653,7 @@ export function useToolActions(modalApi: ModalApi) {
       await refreshTool(tool.id);
     } catch (e) {
       toast.error(
-        `Failed: ${e instanceof Error ? e.message : String(e)}`
+        `Failed: ${explainToolError(e instanceof Error ? e.message : String(e))}`
       );
     }
   };
@@ -39,6 +39,8 @@ export interface Settings {
   maxConcurrency: number;
   maxRetries: number;
   maxSpeed: number; // in KB/s, 0 = unlimited
+  /** Tool installs (yt-dlp, FFmpeg, ...) run at once during setup and batch updates. */
+  toolInstallConcurrency: number;
   fileCollision: FileCollisionAction;
   skipDownloadedBefore: boolean;
   saveMetadataFiles: boolean;
@@ -97,6 +99,22 @@ export interface Settings {
   /** Lite: user dismissed the missing-Deno card. Tools can still install later. */
   denoJsRuntimeSkipped: boolean;
 
+  // Network (HalalDL's own requests and the tools it launches)
+  /** `http://`, `https://`, `socks5://` or `socks5h://` URL without credentials. Empty = direct. */
+  proxyUrl: string;
+  /** Proxy user; the password is kept in the OS credential store, not in settings. */
+  proxyUsername: string;
+  connectTimeoutSecs: number; // 0 = default
+  readTimeoutSecs: number; // 0 = default
+  /** Local IP address outbound connections bind to. Empty = let the OS choose. */
+  bindAddress: string;
+  /** Extra PEM bundle or certificate directory to trust (TLS-inspecting proxies). */
+  caCertificatesPath: string;
+  /** Cap for HalalDL's own transfers (tool downloads), in KB/s, 0 = unlimited. */
+  toolDownloadMaxSpeed: number;
+  /** Extra cap for unattended startup tool installs, in KB/s, 0 = unlimited. */
+  backgroundToolDownloadMaxSpeed: number;
+
   // History
   historyRetention: number; // max entries to keep, 0 = unlimited
   watchlistDeliveryMode: WatchlistDeliveryMode;
@@ -116,6 +134,7 @@ export const DEFAULT_SETTINGS: Settings = {
   maxConcurrency: 2,
   maxRetries: 3,
   maxSpeed: 0,
+  toolInstallConcurrency: 3,
   fileCollision: "rename",
   skipDownloadedBefore: false,
   saveMetadataFiles: false,
@@ -150,12 +169,27 @@ export const DEFAULT_SETTINGS: Settings = {
   cookiesFilePath: "",
   denoJsRuntimePath: "",
   denoJsRuntimeSkipped: false,
+  proxyUrl: "",
+  proxyUsername: "",
+  connectTimeoutSecs: 0,
+  readTimeoutSecs: 0,
+  bindAddress: "",
+  caCertificatesPath: "",
+  toolDownloadMaxSpeed: 0,
+  backgroundToolDownloadMaxSpeed: 0,
   historyRetention: 0,
   watchlistDeliveryMode: "ask",
 };
 
 export const SETTINGS_KEYS = Object.keys(DEFAULT_SETTINGS) as (keyof Settings)[];
 
+/** Upper bound `download_tools` applies to parallel installs. */
+export const TOOL_INSTALL_CONCURRENCY_MAX = 8;
+
+function normalizeNonNegativeInt(value: unknown): number {
+  return typeof value === "number" && Number.isFinite(value) && value > 0 ? Math.round(value) : 0;
+}
+
 function normalizeSettings(settings: Settings): Settings {
   // The option moved to presets. Drop the legacy global key when old settings load.
   const currentSettings = { ...settings } as Settings & { squareAlbumArt?: unknown };
@@ -177,6 +211,19 @@ function normalizeSettings(settings: Settings): Settings {
     denoJsRuntimePath:
       typeof settings.denoJsRuntimePath === "string" ? settings.denoJsRuntimePath.trim() : "",
     denoJsRuntimeSkipped: settings.denoJsRuntimeSkipped === true,
+    proxyUrl: typeof settings.proxyUrl === "string" ? settings.proxyUrl.trim() : "",
+    proxyUsername: typeof settings.proxyUsername === "string" ? settings.proxyUsername.trim() : "",
+    connectTimeoutSecs: normalizeNonNegativeInt(settings.connectTimeoutSecs),
+    readTimeoutSecs: normalizeNonNegativeInt(settings.readTimeoutSecs),
+    bindAddress: typeof settings.bindAddress === "string" ? settings.bindAddress.trim() : "",
+    caCertificatesPath:
+      typeof settings.caCertificatesPath === "string" ? settings.caCertificatesPath.trim() : "",
+    toolDownloadMaxSpeed: normalizeNonNegativeInt(settings.toolDownloadMaxSpeed),
+    backgroundToolDownloadMaxSpeed: normalizeNonNegativeInt(settings.backgroundToolDownloadMaxSpeed),
+    toolInstallConcurrency: Math.min(
+      TOOL_INSTALL_CONCURRENCY_MAX,
+      normalizeNonNegativeInt(settings.toolInstallConcurrency) || DEFAULT_SETTINGS.toolInstallConcurrency
+    ),
   };
 }
 